@@ -0,0 +1,96 @@
+extern crate rusty_box;
+
+use rusty_box::problems::{self, Problem, PROBLEMS};
+use std::env;
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage:
+    rustybox list                 List all the solved problems
+    rustybox solve <n>...         Solve the given problems
+    rustybox solve --all          Solve all the problems
+    rustybox verify [<n>...]      Check solutions against the known answers (all by default)";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("list") => {
+            list();
+            Ok(true)
+        },
+        Some("solve") => selected(&args[1..], false).map(|ps| solve(&ps, false)),
+        Some("verify") => selected(&args[1..], true).map(|ps| solve(&ps, true)),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2)
+        }
+    }
+}
+
+fn list() {
+    for p in PROBLEMS {
+        println!("{:>4}  {}", p.number, p.title);
+    }
+}
+
+/// The problems named on the command line, or all of them if `--all` is given
+/// (or nothing is given and `all_by_default` is set)
+fn selected(args: &[String], all_by_default: bool) -> Result<Vec<&'static Problem>, String> {
+    if args.iter().any(|a| a == "--all") || (args.is_empty() && all_by_default) {
+        return Ok(PROBLEMS.iter().collect());
+    }
+
+    if args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    args.iter()
+        .map(|a| {
+            let number = a.parse().map_err(|_| format!("Invalid problem number: {}", a))?;
+            problems::find(number).ok_or(format!("Problem {} is not solved yet", number))
+        })
+        .collect()
+}
+
+/// Solves each problem while timing it, returns whether all the answers were correct
+fn solve(ps: &[&Problem], verbose_check: bool) -> bool {
+    let mut mismatches = 0;
+    let mut total = Duration::new(0, 0);
+
+    for p in ps {
+        let start = Instant::now();
+        let answer = p.solve();
+        let elapsed = start.elapsed();
+        total += elapsed;
+
+        let status = if answer == p.answer {
+            if verbose_check { "OK" } else { "" }
+        } else {
+            mismatches += 1;
+            "MISMATCH"
+        };
+
+        let line = format!("{:>4}  {:<40} {:>16}  {:>10}  {}", p.number, p.title, answer, millis(elapsed), status);
+        println!("{}", line.trim_end());
+        if answer != p.answer {
+            println!("      expected {}", p.answer);
+        }
+    }
+
+    if verbose_check {
+        println!("{} of {} problems correct in {}", ps.len() - mismatches, ps.len(), millis(total));
+    }
+
+    mismatches == 0
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs() as f64 * 1e3 + d.subsec_nanos() as f64 / 1e6)
+}
@@ -0,0 +1,356 @@
+use days::{days_in_month, Date, DayOfWeek, MAX_YEAR, MIN_YEAR, MONTH_NAMES, WEEKDAYS, WEEKDAY_NAMES};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// When a holiday falls in a given year
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HolidayRule {
+    /// The same day and month every year, skipped in years without it like 29 February
+    Fixed { day: u8, month: u8 },
+    /// The nth weekday of the month, negative counting from the end so -1 is the last
+    NthWeekday { nth: i8, weekday: DayOfWeek, month: u8 },
+    /// Days after Easter Sunday, negative for days before it
+    Easter(i16),
+    /// A single date
+    Once(Date),
+}
+
+impl HolidayRule {
+
+    /// The day the holiday falls on in the given year, if it does
+    pub fn date_in(&self, year: i32) -> Option<Date> {
+        match *self {
+            HolidayRule::Fixed { day, month } => Date::new(day, month, year).ok(),
+            HolidayRule::NthWeekday { nth, weekday, month } => nth_weekday_of_month(nth, weekday, month, year),
+            HolidayRule::Easter(offset) => easter_sunday(year).checked_add(offset as i64).ok(),
+            HolidayRule::Once(date) => Some(date).filter(|d| d.year == year),
+        }
+    }
+
+    /// The days of the holiday falling within the given year, in date order. Unlike `date_in`
+    /// this takes in days far enough from Easter to belong to another year's Easter
+    pub fn dates_in(&self, year: i32) -> Vec<Date> {
+        match *self {
+            HolidayRule::Easter(offset) => {
+                // Easter Sunday is the 80th to 116th day of the year, counting from 0
+                let offset = offset as i32;
+                let earliest = year - (offset + 116).div_euclid(365) - 1;
+                let latest = year - (offset + 80).div_euclid(366) + 1;
+                (earliest.max(MIN_YEAR)..latest.min(MAX_YEAR) + 1)
+                    .filter_map(|easter_year| self.date_in(easter_year))
+                    .filter(|d| d.year == year)
+                    .collect()
+            },
+            _ => self.date_in(year).into_iter().collect(),
+        }
+    }
+}
+
+/// The nth weekday of the month, negative counting from the end
+pub fn nth_weekday_of_month(nth: i8, weekday: DayOfWeek, month: u8, year: i32) -> Option<Date> {
+    let length = days_in_month(month, year).ok()? as i64;
+    let first = Date::new(1, month, year).ok()?;
+    let first_offset = first.day_of_week.days_until(weekday) as i64;
+    let count = (length - first_offset + 6) / 7;
+    let week = match nth {
+        n if n > 0 => n as i64 - 1,
+        n if n < 0 => count + n as i64,
+        _ => return None,
+    };
+    if week >= 0 && week < count { Some(first + first_offset + 7 * week) } else { None }
+}
+
+/// Easter Sunday of the given year by the Gregorian computus (the anonymous algorithm of Meeus, Jones and Butcher)
+pub fn easter_sunday(year: i32) -> Date {
+    let y = year as i64;
+    let golden = y.rem_euclid(19);
+    let century = y.div_euclid(100);
+    let year_of_century = y.rem_euclid(100);
+    let lunar_correction = (century - (century + 8).div_euclid(25) + 1).div_euclid(3);
+    // Age of the moon on 22 March, which makes the full moon this many days later
+    let epact = (19 * golden + century - century.div_euclid(4) - lunar_correction + 15).rem_euclid(30);
+    // Days from that full moon to the next Sunday
+    let to_sunday = (32 + 2 * century.rem_euclid(4) + 2 * (year_of_century / 4) - epact - year_of_century % 4).rem_euclid(7);
+    let late = (golden + 11 * epact + 22 * to_sunday) / 451;
+    let days_after_march = epact + to_sunday - 7 * late + 114;
+
+    Date::from_without_weekday((days_after_march % 31 + 1) as u8, (days_after_march / 31) as u8, year)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Holiday {
+    pub name: String,
+    pub rule: HolidayRule,
+}
+
+/// Days off: weekends plus named holidays, for counting and stepping over working days
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HolidayCalendar {
+    holidays: Vec<Holiday>,
+    weekend: Vec<DayOfWeek>,
+}
+
+impl HolidayCalendar {
+
+    /// No holidays, with Saturday and Sunday as the weekend
+    pub fn new() -> HolidayCalendar {
+        HolidayCalendar::with_weekend(vec![DayOfWeek::Saturday, DayOfWeek::Sunday])
+    }
+
+    /// No holidays, with the given days as the weekend
+    pub fn with_weekend(weekend: Vec<DayOfWeek>) -> HolidayCalendar {
+        assert!(WEEKDAYS.iter().any(|d| !weekend.contains(d)), "A week needs at least one business day");
+        HolidayCalendar { holidays: Vec::new(), weekend }
+    }
+
+    pub fn add(&mut self, name: &str, rule: HolidayRule) {
+        self.holidays.push(Holiday { name: name.to_string(), rule });
+    }
+
+    pub fn holidays(&self) -> &[Holiday] {
+        &self.holidays
+    }
+
+    /// The holidays falling in the given year, in date order
+    pub fn holidays_in(&self, year: i32) -> Vec<(Date, &str)> {
+        let mut days: Vec<(Date, &str)> = self.holidays
+            .iter()
+            .flat_map(|h| h.rule.dates_in(year).into_iter().map(move |d| (d, h.name.as_str())))
+            .collect();
+        days.sort_by_key(|&(d, _)| d.days_since_epoch());
+        days
+    }
+
+    pub fn is_weekend(&self, date: Date) -> bool {
+        self.weekend.contains(&date.day_of_week)
+    }
+
+    pub fn is_holiday(&self, date: Date) -> bool {
+        self.holidays.iter().any(|h| h.rule.dates_in(date.year).contains(&date))
+    }
+
+    pub fn is_business_day(&self, date: Date) -> bool {
+        !self.is_weekend(date) && !self.is_holiday(date)
+    }
+
+    /// The business day the given number of business days after date, or before it if negative.
+    /// Adding none gives back date, even if it is not a business day
+    pub fn add_business_days(&self, date: Date, days: i64) -> Date {
+        let step = days.signum();
+        let mut remaining = days.abs();
+        let mut date = date;
+        while remaining > 0 {
+            date = date + step;
+            if self.is_business_day(date) {
+                remaining -= 1;
+            }
+        }
+        date
+    }
+
+    /// Number of business days from start up to but excluding end, negative if end is before start,
+    /// so that `add_business_days(start, business_days_between(start, end)) == end` for business days
+    pub fn business_days_between(&self, start: Date, end: Date) -> i64 {
+        if end < start {
+            return -self.business_days_between(end, start);
+        }
+
+        let days = end - start;
+        let per_week = WEEKDAYS.iter().filter(|d| !self.weekend.contains(d)).count() as i64;
+        let remainder = (days - days % 7..days).filter(|&i| !self.is_weekend(start + i)).count() as i64;
+
+        let mut holidays: Vec<i64> = (start.year..end.year + 1)
+            .flat_map(|year| self.holidays.iter().flat_map(move |h| h.rule.dates_in(year)))
+            .filter(|&d| d >= start && d < end && !self.is_weekend(d))
+            .map(|d| d.days_since_epoch())
+            .collect();
+        holidays.sort();
+        holidays.dedup();
+
+        days / 7 * per_week + remainder - holidays.len() as i64
+    }
+}
+
+impl Default for HolidayCalendar {
+    fn default() -> HolidayCalendar {
+        HolidayCalendar::new()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseHolidayError {
+    /// Line of the rule file, counting from 1
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseHolidayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: invalid holiday rule {:?}", self.line, self.text)
+    }
+}
+
+impl Error for ParseHolidayError {}
+
+/// Parses a rule file, with a `name: rule` line per holiday and `#` starting a comment. Rules are
+/// `12-25` for a fixed day, `4 Thursday November` or `-1 Monday May` for the nth weekday of a month,
+/// `Easter`, `Easter+1` or `Easter-2` for days around Easter, and `2011-04-29` for a single date.
+/// The weekend is Saturday and Sunday
+impl FromStr for HolidayCalendar {
+    type Err = ParseHolidayError;
+
+    fn from_str(s: &str) -> Result<HolidayCalendar, ParseHolidayError> {
+        let mut calendar = HolidayCalendar::new();
+
+        for (ix, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = || ParseHolidayError { line: ix + 1, text: line.to_string() };
+
+            let mut name_rule = line.splitn(2, ':');
+            let name = name_rule.next().unwrap_or("").trim();
+            let rule = name_rule.next().and_then(|r| parse_rule(r.trim())).ok_or_else(error)?;
+            if name.is_empty() {
+                return Err(error());
+            }
+            calendar.add(name, rule);
+        }
+
+        Ok(calendar)
+    }
+}
+
+fn parse_rule(rule: &str) -> Option<HolidayRule> {
+    let words: Vec<&str> = rule.split_whitespace().collect();
+
+    if words.len() == 3 {
+        let nth = words[0].trim_start_matches('+').parse().ok().filter(|&n: &i8| n != 0 && n.abs() <= 5)?;
+        let weekday = WEEKDAYS[find_name(&WEEKDAY_NAMES, words[1])?];
+        let month = find_name(&MONTH_NAMES, words[2])? as u8 + 1;
+        return Some(HolidayRule::NthWeekday { nth, weekday, month });
+    }
+    if words.len() != 1 {
+        return None;
+    }
+
+    // Compared before slicing further, as the sixth byte may be inside a multibyte character
+    if rule.get(..6).map_or(false, |s| s.eq_ignore_ascii_case("easter")) {
+        let offset = &rule[6..];
+        return match offset {
+            "" => Some(HolidayRule::Easter(0)),
+            _ if offset.starts_with('+') => offset[1..].parse().ok().map(HolidayRule::Easter),
+            _ => offset.parse().ok().filter(|&o: &i16| o < 0).map(HolidayRule::Easter),
+        };
+    }
+
+    let parts: Vec<&str> = rule.split('-').collect();
+    if parts.len() == 2 && parts.iter().all(|p| p.len() == 2) {
+        let month = parts[0].parse().ok()?;
+        let day = parts[1].parse().ok()?;
+        // Check against a leap year so 02-29 is allowed
+        Date::new(day, month, 2000).ok()?;
+        return Some(HolidayRule::Fixed { day, month });
+    }
+
+    rule.parse().ok().map(HolidayRule::Once)
+}
+
+/// Index of the name matching the word in full or by its first three letters, ignoring case
+fn find_name(names: &[&str], word: &str) -> Option<usize> {
+    names.iter().position(|name| {
+        name.eq_ignore_ascii_case(word) || (word.len() == 3 && name[..3].eq_ignore_ascii_case(word))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn date(day: u8, month: u8, year: i32) -> Date {
+        Date::from_without_weekday(day, month, year)
+    }
+
+    fn us_holidays() -> HolidayCalendar {
+        include_str!("../../data/us_federal_holidays.txt").parse().unwrap()
+    }
+
+    #[test]
+    fn easter_by_the_computus() {
+        assert_eq!(easter_sunday(1961), date(2, 4, 1961));
+        assert_eq!(easter_sunday(2000), date(23, 4, 2000));
+        assert_eq!(easter_sunday(2008), date(23, 3, 2008));
+        assert_eq!(easter_sunday(2038), date(25, 4, 2038));
+        assert_eq!(easter_sunday(2019), date(21, 4, 2019));
+
+        for year in 1583..3000 {
+            let easter = easter_sunday(year);
+            assert_eq!(easter.day_of_week, DayOfWeek::Sunday);
+            assert!(easter >= date(22, 3, year) && easter <= date(25, 4, year));
+        }
+    }
+
+    #[test]
+    fn holiday_rules() {
+        let calendar = us_holidays();
+        let holidays_2017: Vec<String> = calendar.holidays_in(2017).iter().map(|&(d, name)| format!("{} {}", d, name)).collect();
+
+        assert_eq!(holidays_2017[..4].to_vec(), vec![
+            "2017-01-01 New Year's Day",
+            "2017-01-16 Martin Luther King Jr. Day",
+            "2017-02-20 Washington's Birthday",
+            "2017-04-14 Good Friday",
+        ]);
+        assert!(calendar.is_holiday(date(23, 11, 2017)));
+        assert!(calendar.is_holiday(date(29, 5, 2017)));
+        assert_eq!(nth_weekday_of_month(5, DayOfWeek::Monday, 2, 2017), None);
+        assert_eq!(HolidayRule::Fixed { day: 29, month: 2 }.date_in(2017), None);
+    }
+
+    #[test]
+    fn easter_offsets_crossing_a_year() {
+        let rule = HolidayRule::Easter(-100);
+        assert_eq!(rule.dates_in(2017), vec![date(6, 1, 2017), date(22, 12, 2017)]);
+        assert_eq!(HolidayRule::Easter(300).dates_in(2018), vec![date(10, 2, 2018)]);
+
+        let mut calendar = HolidayCalendar::new();
+        calendar.add("Advent", rule);
+        assert!(calendar.is_holiday(date(22, 12, 2017)));
+        assert_eq!(calendar.holidays_in(2017).len(), 2);
+        assert_eq!(calendar.business_days_between(date(1, 12, 2017), date(1, 1, 2018)), 20);
+    }
+
+    #[test]
+    fn business_day_arithmetic() {
+        let calendar = us_holidays();
+        let wednesday = date(22, 11, 2017);
+
+        assert_eq!(calendar.add_business_days(wednesday, 1), date(24, 11, 2017));
+        assert_eq!(calendar.add_business_days(wednesday, 2), date(27, 11, 2017));
+        assert_eq!(calendar.add_business_days(date(27, 11, 2017), -2), wednesday);
+        assert_eq!(calendar.add_business_days(date(25, 11, 2017), 0), date(25, 11, 2017));
+        assert_eq!(calendar.business_days_between(wednesday, date(27, 11, 2017)), 2);
+        assert_eq!(calendar.business_days_between(date(27, 11, 2017), wednesday), -2);
+        assert_eq!(HolidayCalendar::new().business_days_between(date(1, 1, 2018), date(1, 1, 2019)), 261);
+
+        let start = date(1, 12, 2016);
+        let mut day = start;
+        for count in 0..300 {
+            assert_eq!(calendar.business_days_between(start, day), count);
+            day = calendar.add_business_days(day, 1);
+        }
+    }
+
+    #[test]
+    fn rule_file_errors() {
+        assert_eq!("Bad: 13-01".parse::<HolidayCalendar>(), Err(ParseHolidayError { line: 1, text: "Bad: 13-01".to_string() }));
+        assert_eq!("X: Easteé".parse::<HolidayCalendar>(), Err(ParseHolidayError { line: 1, text: "X: Easteé".to_string() }));
+        assert!("\n# comment\nNo rule".parse::<HolidayCalendar>().unwrap_err().line == 3);
+        assert!("Day: 0 Monday May".parse::<HolidayCalendar>().is_err());
+        assert!(": Easter".parse::<HolidayCalendar>().is_err());
+        assert_eq!("Wedding: 2011-04-29".parse::<HolidayCalendar>().map(|c| c.holidays_in(2011).len()), Ok(1));
+    }
+}
@@ -0,0 +1,310 @@
+use days::{is_leap_year, month_length, Date, DateError, DayOfWeek, MAX_YEAR, MIN_YEAR};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A day as reckoned by some calendar, which may differ from the Gregorian one of `Date`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CalendarDate {
+    pub day: u8,
+    pub month: u8,
+    pub year: i32,
+}
+
+impl CalendarDate {
+    pub fn new(day: u8, month: u8, year: i32) -> CalendarDate {
+        CalendarDate { day, month, year }
+    }
+}
+
+impl PartialOrd for CalendarDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some((self.year, self.month, self.day).cmp(&(other.year, other.month, other.day)))
+    }
+}
+
+/// A system of naming days by day, month and year, convertible to and from `Date`
+pub trait Calendar {
+
+    fn is_leap_year(&self, year: i32) -> bool;
+
+    /// The last day of the month in the given year
+    fn days_in_month(&self, month: u8, year: i32) -> Result<u8, DateError> {
+        month_length(month, self.is_leap_year(year))
+    }
+
+    /// What this calendar calls the given date
+    fn from_date(&self, date: Date) -> CalendarDate;
+
+    /// The date this calendar's day falls on, failing for days the calendar does not have
+    fn to_date(&self, day: CalendarDate) -> Result<Date, DateError>;
+}
+
+/// The proleptic Gregorian calendar, the one `Date` is based on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Gregorian;
+
+impl Calendar for Gregorian {
+
+    fn is_leap_year(&self, year: i32) -> bool {
+        is_leap_year(year)
+    }
+
+    fn from_date(&self, date: Date) -> CalendarDate {
+        CalendarDate::new(date.day, date.month, date.year)
+    }
+
+    fn to_date(&self, day: CalendarDate) -> Result<Date, DateError> {
+        Date::new(day.day, day.month, day.year)
+    }
+}
+
+/// The proleptic Julian calendar, with a leap year every fourth year
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Julian;
+
+/// Days between Julian 0000-03-01, the start of the first 4 year era, and Gregorian 1970-01-01
+const JULIAN_EPOCH_OFFSET: i64 = 719470;
+
+/// Days in each 4 year era of the Julian calendar
+const DAYS_PER_JULIAN_ERA: i64 = 1461;
+
+impl Calendar for Julian {
+
+    fn is_leap_year(&self, year: i32) -> bool {
+        year % 4 == 0
+    }
+
+    fn from_date(&self, date: Date) -> CalendarDate {
+        let z = date.days_since_epoch() + JULIAN_EPOCH_OFFSET;
+        let era = z.div_euclid(DAYS_PER_JULIAN_ERA);
+        let day_of_era = z - era * DAYS_PER_JULIAN_ERA;
+        let year_of_era = (day_of_era - day_of_era / 1460) / 365;
+        let day_of_year = day_of_era - 365 * year_of_era;
+        let march_based_month = (5 * day_of_year + 2) / 153;
+
+        let day = (day_of_year - (153 * march_based_month + 2) / 5 + 1) as u8;
+        let month = (if march_based_month < 10 { march_based_month + 3 } else { march_based_month - 9 }) as u8;
+        let year = (year_of_era + era * 4) as i32 + if month <= 2 { 1 } else { 0 };
+
+        CalendarDate::new(day, month, year)
+    }
+
+    fn to_date(&self, day: CalendarDate) -> Result<Date, DateError> {
+        if day.year < MIN_YEAR || day.year > MAX_YEAR {
+            return Err(DateError::UnsupportedYear(day.year));
+        }
+        if day.day < 1 || day.day > self.days_in_month(day.month, day.year)? {
+            return Err(DateError::BadDay(day.day));
+        }
+
+        // Counting years from March makes the leap day the last day of the year
+        let year = day.year as i64 - if day.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(4);
+        let year_of_era = year - era * 4;
+        let march_based_month = (day.month as i64 + 9) % 12;
+        let day_of_year = (153 * march_based_month + 2) / 5 + day.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + day_of_year;
+
+        Ok(Date::from_days_since_epoch(era * DAYS_PER_JULIAN_ERA + day_of_era - JULIAN_EPOCH_OFFSET))
+    }
+}
+
+/// The Julian calendar up to a switchover date, and the Gregorian calendar from then on,
+/// skipping the days in between as the countries adopting the Gregorian calendar did
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Switchover {
+    first_gregorian_day: Date,
+}
+
+impl Switchover {
+
+    /// Switches to the Gregorian calendar on the given date
+    pub fn new(first_gregorian_day: Date) -> Switchover {
+        Switchover { first_gregorian_day }
+    }
+
+    /// The original switchover, where Julian 4 October 1582 was followed by Gregorian 15 October 1582
+    pub fn papal() -> Switchover {
+        Switchover::new(Date::from_without_weekday(15, 10, 1582))
+    }
+
+    /// Great Britain and its colonies, where Julian 2 September 1752 was followed by Gregorian 14 September 1752
+    pub fn british() -> Switchover {
+        Switchover::new(Date::from_without_weekday(14, 9, 1752))
+    }
+
+    pub fn first_gregorian_day(&self) -> Date {
+        self.first_gregorian_day
+    }
+}
+
+impl Default for Switchover {
+    fn default() -> Switchover {
+        Switchover::papal()
+    }
+}
+
+impl Calendar for Switchover {
+
+    /// In the switchover year itself, February is Julian if its Julian leap day comes before the switch
+    fn is_leap_year(&self, year: i32) -> bool {
+        if year < self.first_gregorian_day.year {
+            return Julian.is_leap_year(year);
+        }
+        if year == self.first_gregorian_day.year {
+            if let Ok(leap_day) = Julian.to_date(CalendarDate::new(29, 2, year)) {
+                if leap_day < self.first_gregorian_day {
+                    return true;
+                }
+            }
+        }
+        Gregorian.is_leap_year(year)
+    }
+
+    fn from_date(&self, date: Date) -> CalendarDate {
+        if date >= self.first_gregorian_day {
+            Gregorian.from_date(date)
+        } else {
+            Julian.from_date(date)
+        }
+    }
+
+    fn to_date(&self, day: CalendarDate) -> Result<Date, DateError> {
+        if day >= Gregorian.from_date(self.first_gregorian_day) {
+            return Gregorian.to_date(day);
+        }
+        let date = Julian.to_date(day)?;
+        if date >= self.first_gregorian_day {
+            // Skipped over by the switchover
+            Err(DateError::BadDay(day.day))
+        } else {
+            Ok(date)
+        }
+    }
+}
+
+/// A date in the ISO-8601 week calendar: the weekday of a week numbered within its ISO year.
+/// Weeks start on Monday, and the first week of an ISO year is the one with its first Thursday
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IsoWeekDate {
+    pub year: i32,
+    pub week: u8, // 1..53
+    pub weekday: DayOfWeek,
+}
+
+impl IsoWeekDate {
+
+    /// A validated ISO week date
+    pub fn new(year: i32, week: u8, weekday: DayOfWeek) -> Result<IsoWeekDate, DateError> {
+        if year < MIN_YEAR || year > MAX_YEAR {
+            return Err(DateError::UnsupportedYear(year));
+        }
+        if week < 1 || week > iso_weeks_in_year(year) {
+            return Err(DateError::BadWeek(week));
+        }
+        Ok(IsoWeekDate { year, week, weekday })
+    }
+
+    pub fn from_date(date: Date) -> IsoWeekDate {
+        // The week belongs to the ISO year its Thursday falls in
+        let thursday = date + (4 - date.day_of_week.iso_number() as i64);
+        let first_of_year = Date::from_without_weekday(1, 1, thursday.year);
+        IsoWeekDate {
+            year: thursday.year,
+            week: ((thursday - first_of_year) / 7 + 1) as u8,
+            weekday: date.day_of_week,
+        }
+    }
+
+    pub fn to_date(&self) -> Date {
+        first_iso_monday(self.year) + (self.week as i64 - 1) * 7 + DayOfWeek::Monday.days_until(self.weekday) as i64
+    }
+}
+
+/// Formats as `2009-W01-1`
+impl fmt::Display for IsoWeekDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-W{:02}-{}", self.year, self.week, self.weekday.iso_number())
+    }
+}
+
+/// Number of ISO weeks, 52 or 53, in the given ISO year
+pub fn iso_weeks_in_year(year: i32) -> u8 {
+    ((first_iso_monday(year + 1) - first_iso_monday(year)) / 7) as u8
+}
+
+/// Monday of the first ISO week of the year, which is the week containing 4 January
+fn first_iso_monday(year: i32) -> Date {
+    let fourth_of_january = Date::from_without_weekday(4, 1, year);
+    fourth_of_january - DayOfWeek::Monday.days_until(fourth_of_january.day_of_week) as i64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn julian_and_gregorian_conversions() {
+        let last_julian = Julian.to_date(CalendarDate::new(4, 10, 1582)).unwrap();
+
+        assert_eq!(last_julian, Date::from(14, 10, 1582, DayOfWeek::Thursday));
+        assert_eq!(Julian.from_date(Date::from_without_weekday(14, 1, 2000)), CalendarDate::new(1, 1, 2000));
+        assert_eq!(Julian.to_date(CalendarDate::new(29, 2, 1900)), Ok(Date::from_without_weekday(13, 3, 1900)));
+        assert_eq!(Gregorian.to_date(CalendarDate::new(29, 2, 1900)), Err(DateError::BadDay(29)));
+
+        let mut date = Date::from_without_weekday(1, 1, -5);
+        for _ in 0..3000 {
+            assert_eq!(Julian.to_date(Julian.from_date(date)), Ok(date));
+            date = date + 1;
+        }
+    }
+
+    #[test]
+    fn switchover_skips_days() {
+        let papal = Switchover::default();
+        let british = Switchover::british();
+
+        assert_eq!(papal.to_date(CalendarDate::new(4, 10, 1582)).map(|d| d + 1), papal.to_date(CalendarDate::new(15, 10, 1582)));
+        assert_eq!(papal.to_date(CalendarDate::new(10, 10, 1582)), Err(DateError::BadDay(10)));
+        assert_eq!(british.to_date(CalendarDate::new(10, 10, 1582)), Ok(Date::from_without_weekday(20, 10, 1582)));
+        assert_eq!(british.from_date(Date::from_without_weekday(13, 9, 1752)), CalendarDate::new(2, 9, 1752));
+        assert_eq!(british.from_date(Date::from_without_weekday(14, 9, 1752)), CalendarDate::new(14, 9, 1752));
+
+        assert!(papal.is_leap_year(1500));
+        assert!(!papal.is_leap_year(1700));
+        assert!(british.is_leap_year(1700));
+
+        // 1700 is a leap year in the Julian calendar but not the Gregorian one
+        let after_february = Switchover::new(Date::from_without_weekday(14, 9, 1700));
+        let before_february = Switchover::new(Date::from_without_weekday(1, 2, 1700));
+        assert!(after_february.is_leap_year(1700));
+        assert_eq!(after_february.days_in_month(2, 1700), Ok(29));
+        assert_eq!(after_february.to_date(CalendarDate::new(29, 2, 1700)), Ok(Date::from_without_weekday(11, 3, 1700)));
+        assert!(!before_february.is_leap_year(1700));
+        assert_eq!(before_february.days_in_month(2, 1700), Ok(28));
+        assert!(Julian.is_leap_year(1900));
+        assert!(!Gregorian.is_leap_year(1900));
+    }
+
+    #[test]
+    fn iso_week_dates() {
+        let week_date = |d, m, y| IsoWeekDate::from_date(Date::from_without_weekday(d, m, y)).to_string();
+
+        assert_eq!(week_date(29, 12, 2008), "2009-W01-1");
+        assert_eq!(week_date(3, 1, 2010), "2009-W53-7");
+        assert_eq!(week_date(1, 1, 2005), "2004-W53-6");
+        assert_eq!(week_date(31, 12, 2014), "2015-W01-3");
+
+        assert_eq!(IsoWeekDate::new(2009, 53, DayOfWeek::Sunday).map(|w| w.to_date()), Ok(Date::from_without_weekday(3, 1, 2010)));
+        assert_eq!(IsoWeekDate::new(2021, 53, DayOfWeek::Monday), Err(DateError::BadWeek(53)));
+        assert_eq!(iso_weeks_in_year(2020), 53);
+        assert_eq!(iso_weeks_in_year(2021), 52);
+
+        let mut date = Date::from_without_weekday(1, 1, 1999);
+        for _ in 0..3000 {
+            assert_eq!(IsoWeekDate::from_date(date).to_date(), date);
+            date = date + 1;
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use days::{Date, DateError, MONTH_NAMES, WEEKDAYS, WEEKDAY_NAMES};
+use days::weekday::Language;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DateField {
+    Year,
+    Month,
+    Day,
+    Weekday,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParseDateErrorKind {
+    /// The field is not in the expected form
+    Malformed(DateField),
+    /// The field is well-formed but does not make a valid date, like month 13 or 30 February
+    OutOfRange(DateField),
+    /// The format has no directive for a field a date can't do without
+    MissingField(DateField),
+    /// The weekday does not match the one of the date
+    WeekdayMismatch,
+    /// The input does not have a character the format requires
+    ExpectedLiteral(char),
+    TrailingCharacters,
+    /// The format has a `%` directive that's not supported for parsing
+    UnknownDirective(char),
+}
+
+/// Why and where, as a byte offset into the input, parsing a date failed
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ParseDateError {
+    pub kind: ParseDateErrorKind,
+    pub position: usize,
+}
+
+impl ParseDateError {
+    fn new(kind: ParseDateErrorKind, position: usize) -> ParseDateError {
+        ParseDateError { kind, position }
+    }
+}
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseDateErrorKind::Malformed(field) => write!(f, "malformed {:?}", field),
+            ParseDateErrorKind::OutOfRange(field) => write!(f, "{:?} out of range", field),
+            ParseDateErrorKind::MissingField(field) => write!(f, "format has no {:?}", field),
+            ParseDateErrorKind::WeekdayMismatch => write!(f, "weekday does not match the date"),
+            ParseDateErrorKind::ExpectedLiteral(c) => write!(f, "expected '{}'", c),
+            ParseDateErrorKind::TrailingCharacters => write!(f, "unexpected trailing characters"),
+            ParseDateErrorKind::UnknownDirective(c) => write!(f, "unknown directive %{}", c),
+        }?;
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl Error for ParseDateError {}
+
+impl Date {
+
+    /// Formats the date by a strftime-like format, where
+    ///
+    /// * `%Y` is the year, at least 4 digits
+    /// * `%m` and `%d` are the month and the day, 2 digits
+    /// * `%j` is the day of the year, 3 digits
+    /// * `%a` and `%A` are the abbreviated and full weekday names
+    /// * `%b` and `%B` are the abbreviated and full month names, or the month as `%m` does
+    ///   for an unvalidated date whose month is not within 1..12
+    /// * `%%` is a literal `%`
+    ///
+    /// and anything else is copied as is
+    pub fn format(&self, format: &str) -> String {
+        let mut result = String::with_capacity(format.len() + 8);
+        let mut spec = format.chars();
+
+        while let Some(c) = spec.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+            match spec.next() {
+                Some('Y') => result.push_str(&format_year(self.year)),
+                Some('m') => result.push_str(&format!("{:02}", self.month)),
+                Some('d') => result.push_str(&format!("{:02}", self.day)),
+                Some('j') => result.push_str(&format!("{:03}", self.day_of_year())),
+                Some('a') => result.push_str(self.day_of_week.abbreviation(Language::English)),
+                Some('A') => result.push_str(self.day_of_week.name(Language::English)),
+                Some('b') => match self.month_name() {
+                    Some(name) => result.push_str(&name[..3]),
+                    None => result.push_str(&format!("{:02}", self.month)),
+                },
+                Some('B') => match self.month_name() {
+                    Some(name) => result.push_str(name),
+                    None => result.push_str(&format!("{:02}", self.month)),
+                },
+                Some('%') => result.push('%'),
+                Some(other) => {
+                    result.push('%');
+                    result.push(other);
+                },
+                None => result.push('%'),
+            }
+        }
+
+        result
+    }
+
+    /// Parses a date by a format of the same directives as `format` but `%j`.
+    /// Month and day take one or two digits, and names are matched ignoring case
+    pub fn parse_from_str(s: &str, format: &str) -> Result<Date, ParseDateError> {
+        let mut parser = Parser { input: s, position: 0 };
+        let mut year = None;
+        let mut month = None;
+        let mut day = None;
+        let mut weekday = None;
+
+        let mut spec = format.chars();
+        while let Some(c) = spec.next() {
+            let start = parser.position;
+            if c != '%' {
+                parser.literal(c)?;
+                continue;
+            }
+            match spec.next() {
+                Some('Y') => year = Some((parser.year()?, start)),
+                Some('m') => month = Some((parser.number(2, DateField::Month)?, start)),
+                Some('d') => day = Some((parser.number(2, DateField::Day)?, start)),
+                Some('a') => weekday = Some((WEEKDAYS[parser.name(&WEEKDAY_NAMES, true, DateField::Weekday)?], start)),
+                Some('A') => weekday = Some((WEEKDAYS[parser.name(&WEEKDAY_NAMES, false, DateField::Weekday)?], start)),
+                Some('b') => month = Some((parser.name(&MONTH_NAMES, true, DateField::Month)? as u8 + 1, start)),
+                Some('B') => month = Some((parser.name(&MONTH_NAMES, false, DateField::Month)? as u8 + 1, start)),
+                Some('%') => parser.literal('%')?,
+                Some(other) => return Err(ParseDateError::new(ParseDateErrorKind::UnknownDirective(other), start)),
+                None => return Err(ParseDateError::new(ParseDateErrorKind::UnknownDirective('%'), start)),
+            }
+        }
+
+        if parser.position < s.len() {
+            return Err(ParseDateError::new(ParseDateErrorKind::TrailingCharacters, parser.position));
+        }
+
+        let missing = |field| ParseDateError::new(ParseDateErrorKind::MissingField(field), s.len());
+        let (year, year_position) = year.ok_or(missing(DateField::Year))?;
+        let (month, month_position) = month.ok_or(missing(DateField::Month))?;
+        let (day, day_position) = day.ok_or(missing(DateField::Day))?;
+
+        let date = Date::new(day, month, year).map_err(|e| match e {
+            DateError::BadDay(_) => ParseDateError::new(ParseDateErrorKind::OutOfRange(DateField::Day), day_position),
+            DateError::BadMonth(_) => ParseDateError::new(ParseDateErrorKind::OutOfRange(DateField::Month), month_position),
+            _ => ParseDateError::new(ParseDateErrorKind::OutOfRange(DateField::Year), year_position),
+        })?;
+
+        match weekday {
+            Some((weekday, position)) if weekday != date.day_of_week =>
+                Err(ParseDateError::new(ParseDateErrorKind::WeekdayMismatch, position)),
+            _ => Ok(date),
+        }
+    }
+
+    /// 1 for the first of January
+    fn month_name(&self) -> Option<&'static str> {
+        (self.month as usize).checked_sub(1).and_then(|index| MONTH_NAMES.get(index)).cloned()
+    }
+
+    pub fn day_of_year(&self) -> u16 {
+        (*self - Date::from_without_weekday(1, 1, self.year) + 1) as u16
+    }
+}
+
+/// Formats as ISO-8601 `2000-12-31`
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.format("%Y-%m-%d"))
+    }
+}
+
+/// Parses either ISO-8601 `2000-12-31` or `31/12/2000`
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Date, ParseDateError> {
+        if s.contains('/') {
+            Date::parse_from_str(s, "%d/%m/%Y")
+        } else {
+            Date::parse_from_str(s, "%Y-%m-%d")
+        }
+    }
+}
+
+fn format_year(year: i32) -> String {
+    if year < 0 {
+        format!("-{:04}", -(year as i64))
+    } else {
+        format!("{:04}", year)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn literal(&mut self, c: char) -> Result<(), ParseDateError> {
+        if self.rest().starts_with(c) {
+            self.position += c.len_utf8();
+            Ok(())
+        } else {
+            Err(ParseDateError::new(ParseDateErrorKind::ExpectedLiteral(c), self.position))
+        }
+    }
+
+    /// Count of leading ASCII digits, up to max_digits
+    fn digits(&self, max_digits: usize) -> usize {
+        self.rest().bytes().take(max_digits).take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    fn number(&mut self, max_digits: usize, field: DateField) -> Result<u8, ParseDateError> {
+        let length = self.digits(max_digits);
+        if length == 0 {
+            return Err(ParseDateError::new(ParseDateErrorKind::Malformed(field), self.position));
+        }
+        let n = self.rest()[..length].parse().expect("Digits always parse");
+        self.position += length;
+        Ok(n)
+    }
+
+    fn year(&mut self) -> Result<i32, ParseDateError> {
+        let start = self.position;
+        let sign_length = if self.rest().starts_with('-') { 1 } else { 0 };
+        self.position += sign_length;
+        let length = self.digits(usize::max_value());
+        self.position = start;
+
+        if length == 0 {
+            return Err(ParseDateError::new(ParseDateErrorKind::Malformed(DateField::Year), start));
+        }
+        let year = self.rest()[..sign_length + length].parse()
+            .map_err(|_| ParseDateError::new(ParseDateErrorKind::OutOfRange(DateField::Year), start))?;
+        self.position += sign_length + length;
+        Ok(year)
+    }
+
+    /// Index of the name the input starts with, or its 3 letter abbreviation
+    fn name(&mut self, names: &[&str], abbreviated: bool, field: DateField) -> Result<usize, ParseDateError> {
+        for (i, name) in names.iter().enumerate() {
+            let name = if abbreviated { &name[..3] } else { name };
+            let rest = self.rest();
+            if rest.len() >= name.len() && rest.is_char_boundary(name.len()) && rest[..name.len()].eq_ignore_ascii_case(name) {
+                self.position += name.len();
+                return Ok(i);
+            }
+        }
+        Err(ParseDateError::new(ParseDateErrorKind::Malformed(field), self.position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use days::DayOfWeek;
+
+    fn error(kind: ParseDateErrorKind, position: usize) -> Result<Date, ParseDateError> {
+        Err(ParseDateError::new(kind, position))
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        let date = Date::from_without_weekday(31, 12, 2000);
+
+        assert_eq!(date.to_string(), "2000-12-31");
+        assert_eq!("2000-12-31".parse(), Ok(date));
+        assert_eq!("31/12/2000".parse(), Ok(date));
+        assert_eq!("1/2/1999".parse(), Ok(Date::from_without_weekday(1, 2, 1999)));
+        assert_eq!(Date::from_without_weekday(5, 3, -44).to_string(), "-0044-03-05");
+        assert_eq!("-0044-03-05".parse(), Ok(Date::from_without_weekday(5, 3, -44)));
+    }
+
+    #[test]
+    fn format_with_directives() {
+        let date = Date::from_without_weekday(1, 2, 2017);
+
+        assert_eq!(date.format("%Y-%m-%d %a"), "2017-02-01 Wed");
+        assert_eq!(date.format("%A %d %B %Y, day %j, 100%%"), "Wednesday 01 February 2017, day 032, 100%");
+        assert_eq!(date.format("%b %q"), "Feb %q");
+    }
+
+    #[test]
+    fn format_month_names_of_unvalidated_dates() {
+        assert_eq!(Date::from(1, 0, 2017, DayOfWeek::Monday).format("%b %B"), "00 00");
+        assert_eq!(Date::from(1, 13, 2017, DayOfWeek::Monday).format("%b %B"), "13 13");
+    }
+
+    #[test]
+    fn parse_with_directives() {
+        assert_eq!(Date::parse_from_str("2017-02-01 Wed", "%Y-%m-%d %a"), Ok(Date::from_without_weekday(1, 2, 2017)));
+        assert_eq!(Date::parse_from_str("monday 1 JANUARY 1", "%A %d %B %Y"), Ok(Date::from_without_weekday(1, 1, 1)));
+        assert_eq!(Date::parse_from_str("2017-02-01 Thu", "%Y-%m-%d %a"), error(ParseDateErrorKind::WeekdayMismatch, 11));
+        assert_eq!(Date::parse_from_str("2017-02", "%Y-%m"), error(ParseDateErrorKind::MissingField(DateField::Day), 7));
+        assert_eq!(Date::parse_from_str("2017", "%Y %q"), error(ParseDateErrorKind::ExpectedLiteral(' '), 4));
+        assert_eq!(Date::parse_from_str("2017 ", "%Y %q"), error(ParseDateErrorKind::UnknownDirective('q'), 5));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_field() {
+        assert_eq!("2000-13-01".parse::<Date>(), error(ParseDateErrorKind::OutOfRange(DateField::Month), 5));
+        assert_eq!("30/02/2000".parse::<Date>(), error(ParseDateErrorKind::OutOfRange(DateField::Day), 0));
+        assert_eq!("2000-x1-01".parse::<Date>(), error(ParseDateErrorKind::Malformed(DateField::Month), 5));
+        assert_eq!("2000-01-011".parse::<Date>(), error(ParseDateErrorKind::TrailingCharacters, 10));
+        assert_eq!("2000/01".parse::<Date>(), error(ParseDateErrorKind::ExpectedLiteral('/'), 2));
+        assert_eq!("99999999-01-01".parse::<Date>(), error(ParseDateErrorKind::OutOfRange(DateField::Year), 0));
+        assert_eq!("".parse::<Date>(), error(ParseDateErrorKind::Malformed(DateField::Year), 0));
+    }
+}
@@ -0,0 +1,412 @@
+pub mod business;
+pub mod calendars;
+pub mod format;
+pub mod range;
+pub mod recurrence;
+pub mod time;
+pub mod view;
+pub mod weekday;
+
+use self::recurrence::{Frequency, RecurrenceRule, WeekdayRule};
+use enum_traits::*;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+// 19
+
+/// The number of first-of-the-month sundays from 1-1-1901 to 31-12-2000
+pub fn count() -> usize {
+    first_of_month_sundays(1901, 2000)
+}
+
+/// Number of Sundays that fell on the first of a month between the given years (both inclusive)
+pub fn first_of_month_sundays(from_year: i32, to_year: i32) -> usize {
+    let mut rule = RecurrenceRule::new(Frequency::Monthly);
+    rule.by_day = vec![WeekdayRule::every(DayOfWeek::Sunday)];
+    rule.by_month_day = vec![1];
+
+    rule.occurrences(Date::from_without_weekday(1, 1, from_year))
+        .take_while(|d| d.year <= to_year)
+        .count()
+}
+
+#[derive(EnumIndex, EnumToIndex, EnumFromIndex, EnumEnds, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl DayOfWeek {
+
+    /// The day after
+    pub fn succ(&self) -> DayOfWeek {
+        DayOfWeek::from_index(self.index() + 1).unwrap_or(DayOfWeek::first())
+    }
+
+    /// The day before
+    pub fn pred(&self) -> DayOfWeek {
+        if *self == DayOfWeek::first() {
+            DayOfWeek::last()
+        } else {
+            DayOfWeek::from_index(self.index() - 1).expect("Not the first day")
+        }
+    }
+}
+
+const WEEKDAY_NAMES: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+const WEEKDAYS: [DayOfWeek; 7] = [
+    DayOfWeek::Monday,
+    DayOfWeek::Tuesday,
+    DayOfWeek::Wednesday,
+    DayOfWeek::Thursday,
+    DayOfWeek::Friday,
+    DayOfWeek::Saturday,
+    DayOfWeek::Sunday,
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Date {
+    pub day: u8,   // 1..31
+    pub month: u8, // 1..12
+    pub year: i32, // Astronomical numbering, so 0 is 1 BC
+    pub day_of_week: DayOfWeek,
+}
+
+/// Earliest year accepted by `Date::new`
+pub const MIN_YEAR: i32 = -999999;
+
+/// Latest year accepted by `Date::new`
+pub const MAX_YEAR: i32 = 999999;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DateError {
+    /// The day is not within the month, like 31 February
+    BadDay(u8),
+    /// The month is not within 1..12
+    BadMonth(u8),
+    /// The year is outside of MIN_YEAR..MAX_YEAR
+    UnsupportedYear(i32),
+    /// The ISO week is not within the ISO year
+    BadWeek(u8),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DateError::BadDay(day) => write!(f, "day {} is not within the month", day),
+            DateError::BadMonth(month) => write!(f, "invalid month {}", month),
+            DateError::UnsupportedYear(year) => write!(f, "year {} is not within {}..{}", year, MIN_YEAR, MAX_YEAR),
+            DateError::BadWeek(week) => write!(f, "week {} is not within the year", week),
+        }
+    }
+}
+
+impl Error for DateError {}
+
+/// Days between 0000-03-01, the start of the first 400 year era, and 1970-01-01
+const EPOCH_OFFSET: i64 = 719468;
+
+/// Days in each 400 year era of the Gregorian calendar
+const DAYS_PER_ERA: i64 = 146097;
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let ord = if self.year != other.year {
+            self.year.cmp(&other.year)
+        } else {
+            if self.month != other.month {
+                self.month.cmp(&other.month)
+            } else {
+                self.day.cmp(&other.day)
+            }
+        };
+        Some(ord)
+    }
+}
+
+impl Date {
+
+    /// A validated date, with its weekday worked out
+    pub fn new(day: u8, month: u8, year: i32) -> Result<Date, DateError> {
+        if year < MIN_YEAR || year > MAX_YEAR {
+            return Err(DateError::UnsupportedYear(year));
+        }
+        if day < 1 || day > days_in_month(month, year)? {
+            return Err(DateError::BadDay(day));
+        }
+        Ok(Date::from_without_weekday(day, month, year))
+    }
+
+    /// A date from its parts without validating them
+    pub fn from(day: u8, month: u8, year: i32, day_of_week: DayOfWeek) -> Date {
+        Date {
+            day,
+            month,
+            year,
+            day_of_week,
+        }
+    }
+
+    pub fn from_without_weekday(day: u8, month: u8, year: i32) -> Date {
+        Date::from(day, month, year, weekday(day, month, year))
+    }
+
+    /// The date that is the given number of days after 1970-01-01 in the proleptic Gregorian calendar
+    ///
+    /// Panics if the date falls outside of MIN_YEAR..MAX_YEAR; see `checked_from_days_since_epoch`
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        Date::checked_from_days_since_epoch(days).expect("Date out of range")
+    }
+
+    /// The date that is the given number of days after 1970-01-01, or `UnsupportedYear` if it
+    /// falls outside of MIN_YEAR..MAX_YEAR
+    pub fn checked_from_days_since_epoch(days: i64) -> Result<Date, DateError> {
+        let z = match days.checked_add(EPOCH_OFFSET) {
+            Some(z) => z,
+            None => return Err(DateError::UnsupportedYear(i32::max_value())),
+        };
+        let era = z.div_euclid(DAYS_PER_ERA);
+        let day_of_era = z - era * DAYS_PER_ERA;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let march_based_month = (5 * day_of_year + 2) / 153;
+
+        let day = (day_of_year - (153 * march_based_month + 2) / 5 + 1) as u8;
+        let month = (if march_based_month < 10 { march_based_month + 3 } else { march_based_month - 9 }) as u8;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 {
+            let year = year.max(i32::min_value() as i64).min(i32::max_value() as i64);
+            return Err(DateError::UnsupportedYear(year as i32));
+        }
+
+        Ok(Date::from_without_weekday(day, month, year as i32))
+    }
+
+    /// Number of days from 1970-01-01 to this date, negative for earlier dates
+    pub fn days_since_epoch(&self) -> i64 {
+        // Counting years from March makes the leap day the last day of the year
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let march_based_month = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * march_based_month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * DAYS_PER_ERA + day_of_era - EPOCH_OFFSET
+    }
+
+    pub fn next(&self) -> Date {
+        if (self.day + 1) > days_in_month(self.month, self.year).expect("Invalid month in date") {
+            if self.month == 12 {
+                Date {
+                    day: 1,
+                    month: 1,
+                    year: self.year + 1,
+                    day_of_week: self.day_of_week.succ(),
+                }
+            } else {
+                Date {
+                    day: 1,
+                    month: self.month + 1,
+                    year: self.year,
+                    day_of_week: self.day_of_week.succ(),
+                }
+            }
+        } else {
+            Date {
+                day: self.day + 1,
+                month: self.month,
+                year: self.year,
+                day_of_week: self.day_of_week.succ(),
+            }
+        }
+    }
+
+    /// The date the given number of days later, or `UnsupportedYear` if it is out of range
+    pub fn checked_add(&self, days: i64) -> Result<Date, DateError> {
+        match self.days_since_epoch().checked_add(days) {
+            Some(total) => Date::checked_from_days_since_epoch(total),
+            None => Err(DateError::UnsupportedYear(if days < 0 { i32::min_value() } else { i32::max_value() })),
+        }
+    }
+
+    /// The date the given number of days earlier, or `UnsupportedYear` if it is out of range
+    pub fn checked_sub(&self, days: i64) -> Result<Date, DateError> {
+        match self.days_since_epoch().checked_sub(days) {
+            Some(total) => Date::checked_from_days_since_epoch(total),
+            None => Err(DateError::UnsupportedYear(if days > 0 { i32::min_value() } else { i32::max_value() })),
+        }
+    }
+
+    pub fn into_iter(self) -> DateIter {
+        DateIter(self)
+    }
+}
+
+impl Add<i64> for Date {
+    type Output = Date;
+
+    fn add(self, days: i64) -> Date {
+        self.checked_add(days).expect("Date out of range")
+    }
+}
+
+impl Sub<i64> for Date {
+    type Output = Date;
+
+    fn sub(self, days: i64) -> Date {
+        self.checked_sub(days).expect("Date out of range")
+    }
+}
+
+/// Number of days between two dates
+impl Sub<Date> for Date {
+    type Output = i64;
+
+    fn sub(self, other: Date) -> i64 {
+        self.days_since_epoch() - other.days_since_epoch()
+    }
+}
+
+/// Day of the week of a date by Sakamoto's method
+fn weekday(day: u8, month: u8, year: i32) -> DayOfWeek {
+    const MONTH_OFFSETS: [i64; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    const FROM_SUNDAY: [DayOfWeek; 7] = [
+        DayOfWeek::Sunday,
+        DayOfWeek::Monday,
+        DayOfWeek::Tuesday,
+        DayOfWeek::Wednesday,
+        DayOfWeek::Thursday,
+        DayOfWeek::Friday,
+        DayOfWeek::Saturday,
+    ];
+
+    let y = year as i64 - if month < 3 { 1 } else { 0 };
+    let index = y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400)
+        + MONTH_OFFSETS[month as usize - 1] + day as i64;
+    FROM_SUNDAY[index.rem_euclid(7) as usize]
+}
+
+pub struct DateIter(Date);
+
+impl Iterator for DateIter {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        let n = self.0.next();
+        let c = self.0;
+        self.0 = n;
+        Some(c)
+    }
+}
+
+pub fn days_in_month(month: u8, year: i32) -> Result<u8, DateError> {
+    month_length(month, is_leap_year(year))
+}
+
+fn month_length(month: u8, leap_year: bool) -> Result<u8, DateError> {
+    match month {
+        1 => Ok(31),
+        2 => if leap_year {
+            Ok(29)
+        } else {
+            Ok(28)
+        },
+        3 => Ok(31),
+        4 => Ok(30),
+        5 => Ok(31),
+        6 => Ok(30),
+        7 => Ok(31),
+        8 => Ok(31),
+        9 => Ok(30),
+        10 => Ok(31),
+        11 => Ok(30),
+        12 => Ok(31),
+        v => Err(DateError::BadMonth(v)),
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    divides(year, 4) && (!divides(year, 100) || divides(year, 400))
+}
+
+fn divides(x: i32, y: i32) -> bool {
+    x % y == 0
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn day_numbers_round_trip() {
+        assert_eq!(Date::from_without_weekday(1, 1, 1970).days_since_epoch(), 0);
+        assert_eq!(Date::from_without_weekday(31, 12, 1969).days_since_epoch(), -1);
+        assert_eq!(Date::from_without_weekday(1, 3, 2000).days_since_epoch(), 11017);
+
+        let mut date = Date::from_without_weekday(1, 1, -401);
+        for days in date.days_since_epoch()..date.days_since_epoch() + 2 * 146097 {
+            assert_eq!(date.days_since_epoch(), days);
+            assert_eq!(Date::from_days_since_epoch(days), date);
+            date = date.next();
+        }
+    }
+
+    #[test]
+    fn weekdays_of_known_dates() {
+        assert_eq!(Date::from_without_weekday(1, 1, 1900).day_of_week, DayOfWeek::Monday);
+        assert_eq!(Date::from_without_weekday(1, 1, 1970).day_of_week, DayOfWeek::Thursday);
+        assert_eq!(Date::from_without_weekday(29, 2, 2000).day_of_week, DayOfWeek::Tuesday);
+        assert_eq!(Date::from_without_weekday(4, 7, 1776).day_of_week, DayOfWeek::Thursday);
+        assert_eq!(Date::from_without_weekday(1, 1, 2100).day_of_week, DayOfWeek::Friday);
+    }
+
+    #[test]
+    fn date_arithmetic() {
+        let new_year = Date::from_without_weekday(1, 1, 2000);
+
+        assert_eq!(new_year + 59, Date::from_without_weekday(29, 2, 2000));
+        assert_eq!(new_year - 1, Date::from_without_weekday(31, 12, 1999));
+        assert_eq!(Date::from_without_weekday(1, 1, 2001) - new_year, 366);
+        assert_eq!(Date::from_without_weekday(1, 1, 1901) - Date::from_without_weekday(1, 1, 1900), 365);
+    }
+
+    #[test]
+    fn validated_construction() {
+        assert_eq!(Date::new(29, 2, 2000), Ok(Date::from(29, 2, 2000, DayOfWeek::Tuesday)));
+        assert_eq!(Date::new(29, 2, 1900), Err(DateError::BadDay(29)));
+        assert_eq!(Date::new(31, 4, 2017), Err(DateError::BadDay(31)));
+        assert_eq!(Date::new(0, 1, 2017), Err(DateError::BadDay(0)));
+        assert_eq!(Date::new(1, 13, 2017), Err(DateError::BadMonth(13)));
+        assert_eq!(Date::new(1, 0, 2017), Err(DateError::BadMonth(0)));
+        assert_eq!(Date::new(1, 1, MAX_YEAR + 1), Err(DateError::UnsupportedYear(MAX_YEAR + 1)));
+        assert_eq!(days_in_month(13, 2017), Err(DateError::BadMonth(13)));
+    }
+
+    #[test]
+    fn checked_arithmetic_stays_within_supported_years() {
+        let last = Date::from_without_weekday(31, 12, MAX_YEAR);
+        let first = Date::from_without_weekday(1, 1, MIN_YEAR);
+
+        assert_eq!(last.checked_add(0), Ok(last));
+        assert_eq!(last.checked_add(1), Err(DateError::UnsupportedYear(MAX_YEAR + 1)));
+        assert_eq!(first.checked_sub(1), Err(DateError::UnsupportedYear(MIN_YEAR - 1)));
+        assert_eq!(first.checked_add(i64::min_value()), Err(DateError::UnsupportedYear(i32::min_value())));
+        assert_eq!(Date::checked_from_days_since_epoch(i64::max_value()), Err(DateError::UnsupportedYear(i32::max_value())));
+        assert_eq!(Date::checked_from_days_since_epoch(0), Ok(Date::from_without_weekday(1, 1, 1970)));
+    }
+}
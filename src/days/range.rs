@@ -0,0 +1,121 @@
+use days::Date;
+
+/// A contiguous span of dates, iterable from either end
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DateRange {
+    /// Day number of the next date from the front
+    front: i64,
+    /// Day number just past the next date from the back
+    back: i64,
+}
+
+impl DateRange {
+
+    /// The dates from start up to and including end, empty if end is before start
+    pub fn inclusive(start: Date, end: Date) -> DateRange {
+        DateRange::exclusive(start, end + 1)
+    }
+
+    /// The dates from start up to but excluding end, empty if end is not after start
+    pub fn exclusive(start: Date, end: Date) -> DateRange {
+        let front = start.days_since_epoch();
+        DateRange { front, back: end.days_since_epoch().max(front) }
+    }
+
+    pub fn contains(&self, date: &Date) -> bool {
+        let day = date.days_since_epoch();
+        self.front <= day && day < self.back
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front == self.back
+    }
+}
+
+impl Iterator for DateRange {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        if self.front < self.back {
+            self.front += 1;
+            Some(Date::from_days_since_epoch(self.front - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Date> {
+        // Compared as the remaining length first, since n may not fit in an i64
+        if n as u64 >= (self.back - self.front) as u64 {
+            self.front = self.back;
+        } else {
+            self.front += n as i64;
+        }
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for DateRange {
+    fn next_back(&mut self) -> Option<Date> {
+        if self.front < self.back {
+            self.back -= 1;
+            Some(Date::from_days_since_epoch(self.back))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for DateRange {}
+
+impl Date {
+
+    /// The dates from this one up to and including end
+    pub fn until(self, end: Date) -> DateRange {
+        DateRange::inclusive(self, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn inclusive_and_exclusive_ranges() {
+        let start = Date::from_without_weekday(27, 2, 2000);
+        let end = Date::from_without_weekday(2, 3, 2000);
+
+        let days: Vec<u8> = DateRange::inclusive(start, end).map(|d| d.day).collect();
+        assert_eq!(days, vec![27, 28, 29, 1, 2]);
+
+        let days: Vec<u8> = DateRange::exclusive(start, end).rev().map(|d| d.day).collect();
+        assert_eq!(days, vec![1, 29, 28, 27]);
+
+        assert!(DateRange::exclusive(start, start).is_empty());
+        assert_eq!(DateRange::inclusive(end, start).len(), 0);
+        assert!(start.until(end).contains(&Date::from_without_weekday(29, 2, 2000)));
+        assert!(!start.until(end).contains(&end.next()));
+    }
+
+    #[test]
+    fn ranges_know_their_size_from_both_ends() {
+        let mut century = Date::from_without_weekday(1, 1, 1901).until(Date::from_without_weekday(31, 12, 2000));
+
+        assert_eq!(century.len(), 36525);
+        assert_eq!(century.next_back(), Some(Date::from_without_weekday(31, 12, 2000)));
+        assert_eq!(century.nth(365), Some(Date::from_without_weekday(1, 1, 1902)));
+        assert_eq!(century.len(), 36158);
+        assert_eq!(century.nth(40000), None);
+        assert_eq!(century.next(), None);
+
+        let mut week = Date::from_without_weekday(1, 1, 2000).until(Date::from_without_weekday(8, 1, 2000));
+        assert_eq!(week.nth(usize::max_value()), None);
+        assert_eq!(week.next(), None);
+    }
+}
@@ -0,0 +1,401 @@
+use days::{days_in_month, is_leap_year, Date, DayOfWeek, MAX_YEAR, MIN_YEAR};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A weekday, optionally narrowed to the nth one of the month or year (negative counting from the end)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct WeekdayRule {
+    pub nth: Option<i8>,
+    pub weekday: DayOfWeek,
+}
+
+impl WeekdayRule {
+    pub fn every(weekday: DayOfWeek) -> WeekdayRule {
+        WeekdayRule { nth: None, weekday }
+    }
+
+    pub fn nth(nth: i8, weekday: DayOfWeek) -> WeekdayRule {
+        WeekdayRule { nth: Some(nth), weekday }
+    }
+}
+
+/// A subset of the iCalendar RRULE (RFC 5545) describing a series of dates, like
+/// `FREQ=MONTHLY;BYDAY=SU;BYMONTHDAY=1` for every first of a month falling on a Sunday
+///
+/// Each period of the frequency, every `interval` periods, is expanded into the dates
+/// its BY* parts select, falling back to the day (and month) of the start date
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Maximum number of occurrences
+    pub count: Option<usize>,
+    /// The last date that may occur
+    pub until: Option<Date>,
+    pub by_month: Vec<u8>,
+    /// Days of the month, negative counting from the end so -1 is the last
+    pub by_month_day: Vec<i8>,
+    pub by_day: Vec<WeekdayRule>,
+}
+
+impl RecurrenceRule {
+
+    /// Every period of the given frequency, forever
+    pub fn new(frequency: Frequency) -> RecurrenceRule {
+        RecurrenceRule {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+        }
+    }
+
+    /// The dates the rule selects from start on, in order
+    pub fn occurrences<'a>(&'a self, start: Date) -> Occurrences<'a> {
+        Occurrences { rule: self, start, next_period: 0, pending: Vec::new(), emitted: 0, empty_periods: 0, done: false }
+    }
+
+    /// Number of periods in the 400 year cycle of the Gregorian calendar, after which the
+    /// periods repeat the same dates, so a rule with none in as many periods in a row has none at all
+    fn periods_per_cycle(&self) -> i64 {
+        match self.frequency {
+            Frequency::Daily => 146097,
+            Frequency::Weekly => 20871,
+            Frequency::Monthly => 4800,
+            Frequency::Yearly => 400,
+        }
+    }
+
+    /// The first date of the nth period from the one start is in
+    fn period_start(&self, start: Date, n: i64) -> Option<Date> {
+        let steps = n * self.interval as i64;
+        let date = match self.frequency {
+            Frequency::Daily => start + steps,
+            Frequency::Weekly => start - DayOfWeek::Monday.days_until(start.day_of_week) as i64 + 7 * steps,
+            Frequency::Monthly => {
+                let months = start.year as i64 * 12 + start.month as i64 - 1 + steps;
+                let year = months.div_euclid(12);
+                if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 {
+                    return None;
+                }
+                Date::from_without_weekday(1, (months.rem_euclid(12) + 1) as u8, year as i32)
+            },
+            Frequency::Yearly => {
+                let year = start.year as i64 + steps;
+                if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 {
+                    return None;
+                }
+                Date::from_without_weekday(1, 1, start.year + steps as i32)
+            },
+        };
+        if date.year < MIN_YEAR || date.year > MAX_YEAR { None } else { Some(date) }
+    }
+
+    /// The dates selected in the period starting at the given date, in order
+    fn expand(&self, start: Date, period: Date) -> Vec<Date> {
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                let length = days_in_month(period.month, period.year).expect("Valid month");
+                let selected = self.by_month_day.is_empty()
+                    || self.by_month_day.iter().any(|&d| resolve_month_day(d, length) == Some(period.day));
+                let weekday = self.by_day.is_empty() || self.by_day.iter().any(|r| r.weekday == period.day_of_week);
+                if selected && weekday { vec![period] } else { vec![] }
+            },
+            Frequency::Weekly => {
+                if self.by_day.is_empty() {
+                    vec![period + DayOfWeek::Monday.days_until(start.day_of_week) as i64]
+                } else {
+                    self.by_day.iter().map(|r| period + DayOfWeek::Monday.days_until(r.weekday) as i64).collect()
+                }
+            },
+            Frequency::Monthly => self.expand_month(period.month, period.year, start.day),
+            Frequency::Yearly => {
+                if self.by_month.is_empty() && self.by_month_day.is_empty() && !self.by_day.is_empty() {
+                    let length = if is_leap_year(period.year) { 366 } else { 365 };
+                    self.by_day.iter().flat_map(|r| nth_weekdays(period, length, r)).map(|offset| period + offset).collect()
+                } else {
+                    let months = if !self.by_month.is_empty() || !self.by_month_day.is_empty() || !self.by_day.is_empty() {
+                        (1..13).collect()
+                    } else {
+                        vec![start.month]
+                    };
+                    months.into_iter().flat_map(|m| self.expand_month(m, period.year, start.day)).collect()
+                }
+            },
+        };
+
+        if !self.by_month.is_empty() {
+            dates.retain(|d| self.by_month.contains(&d.month));
+        }
+        dates.sort_by_key(|d| d.days_since_epoch());
+        dates.dedup();
+        dates
+    }
+
+    fn expand_month(&self, month: u8, year: i32, default_day: u8) -> Vec<Date> {
+        let first = Date::from_without_weekday(1, month, year);
+        let length = days_in_month(month, year).expect("Valid month");
+
+        let offsets: Vec<i64> = if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|&d| resolve_month_day(d, length))
+                .map(|d| d as i64 - 1)
+                .filter(|&offset| {
+                    self.by_day.is_empty() || self.by_day.iter().any(|r| nth_weekdays(first, length as i64, r).contains(&offset))
+                })
+                .collect()
+        } else if !self.by_day.is_empty() {
+            self.by_day.iter().flat_map(|r| nth_weekdays(first, length as i64, r)).collect()
+        } else if default_day <= length {
+            vec![default_day as i64 - 1]
+        } else {
+            vec![]
+        };
+
+        offsets.into_iter().map(|offset| first + offset).collect()
+    }
+}
+
+/// Offsets from first, within a span of the given length, of the days the rule selects
+fn nth_weekdays(first: Date, length: i64, rule: &WeekdayRule) -> Vec<i64> {
+    let first_offset = first.day_of_week.days_until(rule.weekday) as i64;
+    let all: Vec<i64> = (0..).map(|week| first_offset + 7 * week).take_while(|&offset| offset < length).collect();
+    match rule.nth {
+        None => all,
+        Some(n) if n > 0 => all.get(n as usize - 1).into_iter().cloned().collect(),
+        Some(n) => {
+            let ix = all.len() as i64 + n as i64;
+            if ix >= 0 { all.get(ix as usize).into_iter().cloned().collect() } else { vec![] }
+        },
+    }
+}
+
+/// The day of the month a possibly negative BYMONTHDAY refers to, if the month has it
+fn resolve_month_day(day: i8, length: u8) -> Option<u8> {
+    let resolved = if day > 0 { day as i16 } else { length as i16 + day as i16 + 1 };
+    if resolved >= 1 && resolved <= length as i16 { Some(resolved as u8) } else { None }
+}
+
+pub struct Occurrences<'a> {
+    rule: &'a RecurrenceRule,
+    start: Date,
+    next_period: i64,
+    /// Dates of the current period yet to be served, in reverse order
+    pending: Vec<Date>,
+    emitted: usize,
+    /// Periods in a row that selected no dates
+    empty_periods: i64,
+    done: bool,
+}
+
+impl<'a> Iterator for Occurrences<'a> {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        while !self.done {
+            if self.rule.count.map_or(false, |count| self.emitted >= count) {
+                self.done = true;
+                break;
+            }
+            match self.pending.pop() {
+                Some(date) => {
+                    if date < self.start {
+                        continue;
+                    }
+                    if self.rule.until.map_or(false, |until| date > until) {
+                        self.done = true;
+                        break;
+                    }
+                    self.emitted += 1;
+                    return Some(date);
+                },
+                None => match self.rule.period_start(self.start, self.next_period) {
+                    Some(period) if self.rule.until.map_or(true, |until| period <= until) => {
+                        self.pending = self.rule.expand(self.start, period);
+                        self.pending.reverse();
+                        self.next_period += 1;
+                        self.empty_periods = if self.pending.is_empty() { self.empty_periods + 1 } else { 0 };
+                        if self.empty_periods >= self.rule.periods_per_cycle() {
+                            self.done = true;
+                        }
+                    },
+                    _ => self.done = true,
+                },
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseRuleError {
+    MissingFrequency,
+    /// A `NAME=VALUE` part whose name is not supported
+    UnknownPart(String),
+    /// A part whose value could not be parsed
+    BadValue(String),
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseRuleError::MissingFrequency => write!(f, "missing FREQ"),
+            ParseRuleError::UnknownPart(ref part) => write!(f, "unsupported rule part {}", part),
+            ParseRuleError::BadValue(ref part) => write!(f, "invalid value in {}", part),
+        }
+    }
+}
+
+impl Error for ParseRuleError {}
+
+/// Parses the `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYMONTH`, `BYMONTHDAY` and `BYDAY` parts of an RRULE
+impl FromStr for RecurrenceRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<RecurrenceRule, ParseRuleError> {
+        let s = s.trim();
+        let s = if s.starts_with("RRULE:") { &s[6..] } else { s };
+
+        let mut frequency = None;
+        let mut rule = RecurrenceRule::new(Frequency::Daily);
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let bad = || ParseRuleError::BadValue(part.to_string());
+            let mut name_value = part.splitn(2, '=');
+            let name = name_value.next().unwrap_or("");
+            let value = name_value.next().ok_or_else(bad)?;
+
+            match name {
+                "FREQ" => frequency = Some(match value {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(bad()),
+                }),
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|&i| i > 0).ok_or_else(bad)?,
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| bad())?),
+                "UNTIL" => rule.until = Some(parse_until(value).ok_or_else(bad)?),
+                "BYMONTH" => rule.by_month = parse_list(value, |v| v.parse().ok().filter(|&m| m >= 1 && m <= 12)).ok_or_else(bad)?,
+                "BYMONTHDAY" => rule.by_month_day = parse_list(value, |v| v.parse().ok().filter(|&d: &i8| d != 0 && d.abs() <= 31)).ok_or_else(bad)?,
+                "BYDAY" => rule.by_day = parse_list(value, parse_weekday_rule).ok_or_else(bad)?,
+                _ => return Err(ParseRuleError::UnknownPart(part.to_string())),
+            }
+        }
+
+        rule.frequency = frequency.ok_or(ParseRuleError::MissingFrequency)?;
+        Ok(rule)
+    }
+}
+
+fn parse_list<T, F: Fn(&str) -> Option<T>>(value: &str, parse: F) -> Option<Vec<T>> {
+    value.split(',').map(|v| parse(v.trim())).collect()
+}
+
+/// `SU`, `1SU`, `+2MO` or `-1FR`
+fn parse_weekday_rule(value: &str) -> Option<WeekdayRule> {
+    if value.len() < 2 || !value.is_char_boundary(value.len() - 2) {
+        return None;
+    }
+    let (nth, code) = value.split_at(value.len() - 2);
+    let weekday = match code {
+        "MO" => DayOfWeek::Monday,
+        "TU" => DayOfWeek::Tuesday,
+        "WE" => DayOfWeek::Wednesday,
+        "TH" => DayOfWeek::Thursday,
+        "FR" => DayOfWeek::Friday,
+        "SA" => DayOfWeek::Saturday,
+        "SU" => DayOfWeek::Sunday,
+        _ => return None,
+    };
+    if nth.is_empty() {
+        return Some(WeekdayRule::every(weekday));
+    }
+    let nth = if nth.starts_with('+') { &nth[1..] } else { nth };
+    nth.parse().ok().filter(|&n: &i8| n != 0 && n.abs() <= 53).map(|n| WeekdayRule::nth(n, weekday))
+}
+
+/// iCalendar `20001231`, optionally followed by a time like `T235959Z`, or ISO-8601 `2000-12-31`
+fn parse_until(value: &str) -> Option<Date> {
+    let date = value.split('T').next().unwrap_or("");
+    if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) {
+        Date::new(date[6..].parse().ok()?, date[4..6].parse().ok()?, date[..4].parse().ok()?).ok()
+    } else {
+        value.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn dates(rule: &str, start: Date) -> Vec<String> {
+        let rule: RecurrenceRule = rule.parse().unwrap();
+        rule.occurrences(start).take(10).map(|d| d.to_string()).collect()
+    }
+
+    fn date(day: u8, month: u8, year: i32) -> Date {
+        Date::from_without_weekday(day, month, year)
+    }
+
+    #[test]
+    fn first_of_month_sundays() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYDAY=SU;BYMONTHDAY=1;UNTIL=20001231".parse().unwrap();
+
+        assert_eq!(rule.occurrences(date(1, 1, 1901)).count(), 171);
+    }
+
+    #[test]
+    fn monthly_and_yearly_rules() {
+        assert_eq!(dates("FREQ=MONTHLY;BYDAY=-1FR;COUNT=3", date(1, 1, 2017)), vec!["2017-01-27", "2017-02-24", "2017-03-31"]);
+        assert_eq!(dates("FREQ=MONTHLY;BYMONTHDAY=31;COUNT=3", date(1, 1, 2017)), vec!["2017-01-31", "2017-03-31", "2017-05-31"]);
+        assert_eq!(dates("FREQ=MONTHLY;INTERVAL=6;COUNT=3", date(15, 3, 2017)), vec!["2017-03-15", "2017-09-15", "2018-03-15"]);
+        assert_eq!(dates("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH;COUNT=2", date(1, 1, 2017)), vec!["2017-11-23", "2018-11-22"]);
+        assert_eq!(dates("FREQ=YEARLY;BYDAY=1MO;COUNT=2", date(1, 1, 2017)), vec!["2017-01-02", "2018-01-01"]);
+        assert_eq!(dates("FREQ=YEARLY;COUNT=2", date(29, 2, 2016)), vec!["2016-02-29", "2020-02-29"]);
+
+        let monthly = RecurrenceRule::new(Frequency::Monthly);
+        assert_eq!(monthly.occurrences(date(15, 3, -5)).take(2).collect::<Vec<_>>(), vec![date(15, 3, -5), date(15, 4, -5)]);
+        assert_eq!(monthly.occurrences(date(30, 11, -1)).take(3).collect::<Vec<_>>(), vec![date(30, 11, -1), date(30, 12, -1), date(30, 1, 0)]);
+    }
+
+    #[test]
+    fn daily_and_weekly_rules() {
+        assert_eq!(dates("FREQ=DAILY;UNTIL=20170105", date(3, 1, 2017)), vec!["2017-01-03", "2017-01-04", "2017-01-05"]);
+        assert_eq!(dates("FREQ=DAILY;BYMONTHDAY=-1;UNTIL=2017-03-31", date(3, 1, 2017)), vec!["2017-01-31", "2017-02-28", "2017-03-31"]);
+        assert_eq!(dates("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;COUNT=4", date(4, 1, 2017)), vec!["2017-01-05", "2017-01-17", "2017-01-19", "2017-01-31"]);
+        assert_eq!(dates("FREQ=WEEKLY;COUNT=2", date(4, 1, 2017)), vec!["2017-01-04", "2017-01-11"]);
+    }
+
+    #[test]
+    fn rules_selecting_no_dates_end() {
+        let none: Vec<&str> = vec![];
+        assert_eq!(dates("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", date(1, 1, 2017)), none);
+        assert_eq!(dates("FREQ=MONTHLY;BYMONTH=4;BYMONTHDAY=31", date(1, 1, 2017)), none);
+        assert_eq!(dates("FREQ=DAILY;BYMONTH=2;BYMONTHDAY=-30", date(1, 1, 2017)), none);
+        assert_eq!(dates("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;COUNT=2", date(1, 1, 2097)), vec!["2104-02-29", "2108-02-29"]);
+    }
+
+    #[test]
+    fn rule_parse_errors() {
+        assert_eq!("COUNT=1".parse::<RecurrenceRule>(), Err(ParseRuleError::MissingFrequency));
+        assert_eq!("FREQ=HOURLY".parse::<RecurrenceRule>(), Err(ParseRuleError::BadValue("FREQ=HOURLY".to_string())));
+        assert_eq!("FREQ=DAILY;BYDAY=0SU".parse::<RecurrenceRule>(), Err(ParseRuleError::BadValue("BYDAY=0SU".to_string())));
+        assert_eq!("FREQ=DAILY;INTERVAL=0".parse::<RecurrenceRule>(), Err(ParseRuleError::BadValue("INTERVAL=0".to_string())));
+        assert_eq!("FREQ=DAILY;WKST=MO".parse::<RecurrenceRule>(), Err(ParseRuleError::UnknownPart("WKST=MO".to_string())));
+    }
+}
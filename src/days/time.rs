@@ -0,0 +1,439 @@
+use days::format::ParseDateError;
+use days::Date;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+use std::time::Duration;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+const NANOS_PER_DAY: i64 = SECONDS_PER_DAY * NANOS_PER_SECOND;
+
+/// A time of day, without leap seconds
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Time {
+    pub hour: u8,        // 0..23
+    pub minute: u8,      // 0..59
+    pub second: u8,      // 0..59
+    pub nanosecond: u32, // 0..999999999
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TimeError {
+    BadHour(u8),
+    BadMinute(u8),
+    /// Not within 0..59, which includes leap seconds
+    BadSecond(u8),
+    BadNanosecond(u32),
+    /// The UTC offset in seconds is not a whole number of minutes within a day
+    BadOffset(i32),
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TimeError::BadHour(hour) => write!(f, "invalid hour {}", hour),
+            TimeError::BadMinute(minute) => write!(f, "invalid minute {}", minute),
+            TimeError::BadSecond(second) => write!(f, "invalid second {}", second),
+            TimeError::BadNanosecond(nanos) => write!(f, "invalid nanosecond {}", nanos),
+            TimeError::BadOffset(seconds) => write!(f, "UTC offset of {} seconds is not whole minutes within a day", seconds),
+        }
+    }
+}
+
+impl Error for TimeError {}
+
+impl Time {
+
+    /// A validated time of day
+    pub fn new(hour: u8, minute: u8, second: u8, nanosecond: u32) -> Result<Time, TimeError> {
+        if hour > 23 {
+            return Err(TimeError::BadHour(hour));
+        }
+        if minute > 59 {
+            return Err(TimeError::BadMinute(minute));
+        }
+        if second > 59 {
+            return Err(TimeError::BadSecond(second));
+        }
+        if nanosecond as i64 >= NANOS_PER_SECOND {
+            return Err(TimeError::BadNanosecond(nanosecond));
+        }
+        Ok(Time { hour, minute, second, nanosecond })
+    }
+
+    pub fn midnight() -> Time {
+        Time { hour: 0, minute: 0, second: 0, nanosecond: 0 }
+    }
+
+    /// The time the given number of nanoseconds after midnight, wrapping around to within a day
+    pub fn from_nanos_since_midnight(nanos: i64) -> Time {
+        let nanos = nanos.rem_euclid(NANOS_PER_DAY);
+        let seconds = nanos / NANOS_PER_SECOND;
+        Time {
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+            nanosecond: (nanos % NANOS_PER_SECOND) as u32,
+        }
+    }
+
+    pub fn nanos_since_midnight(&self) -> i64 {
+        (self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64) * NANOS_PER_SECOND + self.nanosecond as i64
+    }
+}
+
+/// Formats as `23:59:59.5`, with as many fractional digits as the nanoseconds need
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+/// A fixed difference from UTC, east of Greenwich being positive
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct UtcOffset {
+    seconds: i32,
+}
+
+impl UtcOffset {
+
+    pub fn utc() -> UtcOffset {
+        UtcOffset { seconds: 0 }
+    }
+
+    /// An offset of whole minutes, less than a day either way, as `+hh:mm` can show
+    pub fn from_seconds(seconds: i32) -> Result<UtcOffset, TimeError> {
+        if (seconds as i64).abs() >= SECONDS_PER_DAY || seconds % 60 != 0 {
+            return Err(TimeError::BadOffset(seconds));
+        }
+        Ok(UtcOffset { seconds })
+    }
+
+    /// An offset like `-08:00`, where the minutes take the sign of the hours
+    pub fn from_hours_minutes(hours: i8, minutes: u8) -> Result<UtcOffset, TimeError> {
+        if minutes > 59 {
+            return Err(TimeError::BadMinute(minutes));
+        }
+        let seconds = hours.abs() as i32 * 3600 + minutes as i32 * 60;
+        UtcOffset::from_seconds(if hours < 0 { -seconds } else { seconds })
+    }
+
+    pub fn seconds(&self) -> i32 {
+        self.seconds
+    }
+}
+
+/// Formats as `+05:30`, or `Z` for UTC
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.seconds == 0 {
+            return write!(f, "Z");
+        }
+        let sign = if self.seconds < 0 { '-' } else { '+' };
+        let minutes = self.seconds.abs() / 60;
+        write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// A moment in time, as the date and time at a fixed offset from UTC.
+/// Date times compare by the moment, so the same moment at different offsets is equal
+#[derive(Debug, Copy, Clone)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+    pub offset: UtcOffset,
+}
+
+impl DateTime {
+
+    pub fn new(date: Date, time: Time, offset: UtcOffset) -> DateTime {
+        DateTime { date, time, offset }
+    }
+
+    /// The moment the given number of seconds and nanoseconds after 1970-01-01T00:00:00Z, at the given offset
+    pub fn from_timestamp(seconds: i64, nanosecond: u32, offset: UtcOffset) -> DateTime {
+        let nanos = seconds as i128 * NANOS_PER_SECOND as i128 + nanosecond as i128;
+        DateTime::from_utc_nanos(nanos, offset)
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z, rounded down
+    pub fn timestamp(&self) -> i64 {
+        self.utc_nanos().div_euclid(NANOS_PER_SECOND as i128) as i64
+    }
+
+    /// The same moment at another offset
+    pub fn to_offset(&self, offset: UtcOffset) -> DateTime {
+        DateTime::from_utc_nanos(self.utc_nanos(), offset)
+    }
+
+    /// How much later this moment is than an earlier one, or how much earlier it is as the error
+    pub fn duration_since(&self, earlier: &DateTime) -> Result<Duration, Duration> {
+        let nanos = self.utc_nanos() - earlier.utc_nanos();
+        if nanos >= 0 { Ok(nanos_to_duration(nanos)) } else { Err(nanos_to_duration(-nanos)) }
+    }
+
+    /// Nanoseconds since 1970-01-01T00:00:00Z
+    fn utc_nanos(&self) -> i128 {
+        self.local_nanos() - self.offset.seconds as i128 * NANOS_PER_SECOND as i128
+    }
+
+    /// Nanoseconds since 1970-01-01T00:00:00 at the offset
+    fn local_nanos(&self) -> i128 {
+        self.date.days_since_epoch() as i128 * NANOS_PER_DAY as i128 + self.time.nanos_since_midnight() as i128
+    }
+
+    fn from_utc_nanos(nanos: i128, offset: UtcOffset) -> DateTime {
+        let local = nanos + offset.seconds as i128 * NANOS_PER_SECOND as i128;
+        let days = local.div_euclid(NANOS_PER_DAY as i128);
+        DateTime {
+            date: Date::from_days_since_epoch(days as i64),
+            time: Time::from_nanos_since_midnight((local - days * NANOS_PER_DAY as i128) as i64),
+            offset,
+        }
+    }
+}
+
+fn nanos_to_duration(nanos: i128) -> Duration {
+    Duration::new((nanos / NANOS_PER_SECOND as i128) as u64, (nanos % NANOS_PER_SECOND as i128) as u32)
+}
+
+fn duration_to_nanos(duration: Duration) -> i128 {
+    duration.as_secs() as i128 * NANOS_PER_SECOND as i128 + duration.subsec_nanos() as i128
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        self.utc_nanos() == other.utc_nanos()
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &DateTime) -> Ordering {
+        self.utc_nanos().cmp(&other.utc_nanos())
+    }
+}
+
+/// Later by the duration, carrying over into the date as needed
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, duration: Duration) -> DateTime {
+        DateTime::from_utc_nanos(self.utc_nanos() + duration_to_nanos(duration), self.offset)
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, duration: Duration) -> DateTime {
+        DateTime::from_utc_nanos(self.utc_nanos() - duration_to_nanos(duration), self.offset)
+    }
+}
+
+/// Formats as RFC 3339 `1996-12-19T16:39:57.5-08:00`
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}T{}{}", self.date, self.time, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseDateTimeError {
+    /// The date part is invalid
+    Date(ParseDateError),
+    /// The time or offset is well-formed but out of range
+    Time(TimeError),
+    /// Not in RFC 3339 form from the given byte position on
+    Malformed(usize),
+}
+
+impl fmt::Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseDateTimeError::Date(ref e) => write!(f, "invalid date: {}", e),
+            ParseDateTimeError::Time(ref e) => write!(f, "invalid time: {}", e),
+            ParseDateTimeError::Malformed(position) => write!(f, "malformed date time at position {}", position),
+        }
+    }
+}
+
+impl Error for ParseDateTimeError {}
+
+/// Parses RFC 3339 `1985-04-12T23:20:50.52Z` or `1996-12-19 16:39:57-08:00`, with a `T` or a space
+/// between date and time. Fractions of a second beyond nanoseconds are truncated
+impl FromStr for DateTime {
+    type Err = ParseDateTimeError;
+
+    fn from_str(s: &str) -> Result<DateTime, ParseDateTimeError> {
+        let split = s.find(|c| c == 'T' || c == 't' || c == ' ').ok_or(ParseDateTimeError::Malformed(s.len()))?;
+        let date = Date::parse_from_str(&s[..split], "%Y-%m-%d").map_err(ParseDateTimeError::Date)?;
+
+        let mut parser = Parser { input: s, position: split + 1 };
+        let hour = parser.number()?;
+        parser.literal(':')?;
+        let minute = parser.number()?;
+        parser.literal(':')?;
+        let second = parser.number()?;
+        let nanosecond = parser.fraction()?;
+        let time = Time::new(hour, minute, second, nanosecond).map_err(ParseDateTimeError::Time)?;
+
+        let offset_start = parser.position;
+        let offset = match parser.next() {
+            Some('Z') | Some('z') => UtcOffset::utc(),
+            Some(sign) if sign == '+' || sign == '-' => {
+                let hours = parser.number()?;
+                parser.literal(':')?;
+                let minutes = parser.number()?;
+                if minutes > 59 {
+                    return Err(ParseDateTimeError::Time(TimeError::BadMinute(minutes)));
+                }
+                let seconds = hours as i32 * 3600 + minutes as i32 * 60;
+                UtcOffset::from_seconds(if sign == '-' { -seconds } else { seconds }).map_err(ParseDateTimeError::Time)?
+            },
+            _ => return Err(ParseDateTimeError::Malformed(offset_start)),
+        };
+
+        if parser.position < s.len() {
+            return Err(ParseDateTimeError::Malformed(parser.position));
+        }
+        Ok(DateTime::new(date, time, offset))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.input[self.position..].chars().next()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn literal(&mut self, c: char) -> Result<(), ParseDateTimeError> {
+        let start = self.position;
+        if self.next() == Some(c) { Ok(()) } else { Err(ParseDateTimeError::Malformed(start)) }
+    }
+
+    /// Exactly 2 digits
+    fn number(&mut self) -> Result<u8, ParseDateTimeError> {
+        let digits = self.input[self.position..].bytes().take(2).take_while(|b| b.is_ascii_digit()).count();
+        if digits != 2 {
+            return Err(ParseDateTimeError::Malformed(self.position));
+        }
+        self.position += 2;
+        Ok(self.input[self.position - 2..self.position].parse().expect("Two digits"))
+    }
+
+    /// Nanoseconds of an optional `.` and at least one digit
+    fn fraction(&mut self) -> Result<u32, ParseDateTimeError> {
+        if !self.input[self.position..].starts_with('.') {
+            return Ok(0);
+        }
+        self.position += 1;
+        let digits = self.input[self.position..].bytes().take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return Err(ParseDateTimeError::Malformed(self.position));
+        }
+        let fraction = &self.input[self.position..self.position + digits.min(9)];
+        self.position += digits;
+        Ok(fraction.parse::<u32>().expect("Digits") * 10u32.pow(9 - fraction.len() as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn date_time(s: &str) -> DateTime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn times_of_day() {
+        assert_eq!(Time::new(24, 0, 0, 0), Err(TimeError::BadHour(24)));
+        assert_eq!(Time::new(23, 59, 60, 0), Err(TimeError::BadSecond(60)));
+        assert_eq!(Time::new(0, 0, 0, 1_000_000_000), Err(TimeError::BadNanosecond(1_000_000_000)));
+        assert!(Time::new(12, 0, 0, 0).unwrap() > Time::new(11, 59, 59, 999_999_999).unwrap());
+        assert_eq!(Time::from_nanos_since_midnight(-1).to_string(), "23:59:59.999999999");
+        assert_eq!(Time::new(8, 5, 3, 500_000_000).unwrap().to_string(), "08:05:03.5");
+    }
+
+    #[test]
+    fn rfc_3339_round_trip() {
+        for &s in ["1985-04-12T23:20:50.52Z", "1996-12-19T16:39:57-08:00", "1937-01-01T12:00:27.87+00:20", "2000-02-29T00:00:00-00:30"].iter() {
+            assert_eq!(date_time(s).to_string(), s);
+        }
+        assert_eq!(date_time("1990-12-31 23:59:59.123456789123z").to_string(), "1990-12-31T23:59:59.123456789Z");
+        assert_eq!(date_time("2000-02-29T00:00:00-00:30").offset.seconds(), -1800);
+
+        let offset = UtcOffset::from_seconds(-19800).unwrap();
+        let moment = DateTime::new(Date::from_without_weekday(1, 1, 2000), Time::new(0, 0, 0, 0).unwrap(), offset);
+        assert_eq!(date_time(&moment.to_string()).offset, offset);
+    }
+
+    #[test]
+    fn offsets_are_whole_minutes() {
+        assert_eq!(UtcOffset::from_seconds(3630), Err(TimeError::BadOffset(3630)));
+        assert_eq!(UtcOffset::from_seconds(-59), Err(TimeError::BadOffset(-59)));
+        assert_eq!(UtcOffset::from_seconds(-3660).map(|o| o.to_string()), Ok("-01:01".to_string()));
+    }
+
+    #[test]
+    fn rfc_3339_errors() {
+        assert_eq!("1990-12-31".parse::<DateTime>(), Err(ParseDateTimeError::Malformed(10)));
+        assert_eq!("1990-12-31T23:59".parse::<DateTime>(), Err(ParseDateTimeError::Malformed(16)));
+        assert_eq!("1990-12-31T23:59:60Z".parse::<DateTime>(), Err(ParseDateTimeError::Time(TimeError::BadSecond(60))));
+        assert_eq!("1990-12-31T23:59:59".parse::<DateTime>(), Err(ParseDateTimeError::Malformed(19)));
+        assert_eq!("1990-12-31T23:59:59Zulu".parse::<DateTime>(), Err(ParseDateTimeError::Malformed(20)));
+        assert_eq!("1990-12-31T23:59:59+24:00".parse::<DateTime>(), Err(ParseDateTimeError::Time(TimeError::BadOffset(86400))));
+        assert!(match "1990-02-30T00:00:00Z".parse::<DateTime>() { Err(ParseDateTimeError::Date(_)) => true, _ => false });
+    }
+
+    #[test]
+    fn moments_across_offsets() {
+        let pacific = date_time("1996-12-19T16:39:57-08:00");
+        let utc = date_time("1996-12-20T00:39:57Z");
+
+        assert_eq!(pacific, utc);
+        assert_eq!(pacific.to_offset(UtcOffset::utc()).to_string(), "1996-12-20T00:39:57Z");
+        assert_eq!(utc.timestamp(), 851042397);
+        assert_eq!(DateTime::from_timestamp(-1, 0, UtcOffset::utc()).to_string(), "1969-12-31T23:59:59Z");
+        assert!(date_time("2000-01-01T00:00:00+01:00") < date_time("1999-12-31T23:30:00Z"));
+    }
+
+    #[test]
+    fn durations_normalize_across_boundaries() {
+        let new_year = date_time("1999-12-31T23:59:59.5+01:00");
+
+        assert_eq!((new_year + Duration::from_millis(500)).to_string(), "2000-01-01T00:00:00+01:00");
+        assert_eq!((new_year + Duration::from_secs(60 * 86400)).to_string(), "2000-02-29T23:59:59.5+01:00");
+        assert_eq!((new_year - Duration::from_secs(365 * 86400)).to_string(), "1998-12-31T23:59:59.5+01:00");
+
+        let later = date_time("2000-01-01T00:00:00Z");
+        assert_eq!(later.duration_since(&new_year), Ok(Duration::new(3600, 500_000_000)));
+        assert_eq!(new_year.duration_since(&later), Err(Duration::new(3600, 500_000_000)));
+    }
+}
@@ -0,0 +1,308 @@
+use days::weekday::Language;
+use days::{days_in_month, Date, DateError, DayOfWeek, MAX_YEAR, MIN_YEAR, MONTH_NAMES};
+use std::fmt;
+
+/// Width of a month in text, 7 days of 2 digits and a separator
+const MONTH_WIDTH: usize = 20;
+
+/// The most weeks a month can touch
+const MAX_WEEKS: usize = 6;
+
+/// A month laid out as a grid of weeks, like `cal` does
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MonthView {
+    month: u8,
+    year: i32,
+    first_weekday: DayOfWeek,
+}
+
+impl MonthView {
+
+    /// The given month, with weeks starting on Sunday
+    pub fn new(month: u8, year: i32) -> Result<MonthView, DateError> {
+        if year < MIN_YEAR || year > MAX_YEAR {
+            return Err(DateError::UnsupportedYear(year));
+        }
+        days_in_month(month, year)?;
+        Ok(MonthView { month, year, first_weekday: DayOfWeek::Sunday })
+    }
+
+    /// The month the date is in
+    pub fn of(date: Date) -> MonthView {
+        MonthView { month: date.month, year: date.year, first_weekday: DayOfWeek::Sunday }
+    }
+
+    /// The same month with weeks starting on the given day
+    pub fn with_first_weekday(self, first_weekday: DayOfWeek) -> MonthView {
+        MonthView { first_weekday, ..self }
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn first_weekday(&self) -> DayOfWeek {
+        self.first_weekday
+    }
+
+    /// The weekdays in the order of the columns
+    pub fn weekdays(&self) -> [DayOfWeek; 7] {
+        let mut days = [self.first_weekday; 7];
+        for ix in 1..7 {
+            days[ix] = days[ix - 1].succ();
+        }
+        days
+    }
+
+    /// The weeks touching the month, with the days of other months left out
+    pub fn weeks(&self) -> Vec<[Option<Date>; 7]> {
+        let first = Date::from_without_weekday(1, self.month, self.year);
+        let length = days_in_month(self.month, self.year).expect("Valid month") as i64;
+        let leading = self.first_weekday.days_until(first.day_of_week) as i64;
+
+        (0..(leading + length + 6) / 7)
+            .map(|week| {
+                let mut days = [None; 7];
+                for (column, day) in days.iter_mut().enumerate() {
+                    let offset = week * 7 + column as i64 - leading;
+                    if offset >= 0 && offset < length {
+                        *day = Some(first + offset);
+                    }
+                }
+                days
+            })
+            .collect()
+    }
+
+    /// Renders as plain text, like `cal`
+    pub fn to_text(&self) -> String {
+        self.to_text_marking(|_| false)
+    }
+
+    /// Renders as plain text, with a `*` after the days to mark
+    pub fn to_text_marking<F: Fn(&Date) -> bool>(&self, mark: F) -> String {
+        self.text_lines(&mark, false).join("\n") + "\n"
+    }
+
+    /// Renders as an HTML table
+    pub fn to_html(&self) -> String {
+        self.to_html_marking(|_| false)
+    }
+
+    /// Renders as an HTML table, with a `marked` class on the cells of the days to mark
+    pub fn to_html_marking<F: Fn(&Date) -> bool>(&self, mark: F) -> String {
+        let mut html = String::new();
+        html.push_str("<table class=\"month\">\n");
+        html.push_str(&format!("<caption>{}</caption>\n", self.title()));
+        html.push_str("<thead><tr>");
+        for day in self.weekdays().iter() {
+            html.push_str(&format!("<th title=\"{}\">{}</th>", day, header(*day)));
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+        for week in self.weeks() {
+            html.push_str("<tr>");
+            for day in week.iter() {
+                match *day {
+                    Some(ref date) if mark(date) => html.push_str(&format!("<td class=\"marked\">{}</td>", date.day)),
+                    Some(ref date) => html.push_str(&format!("<td>{}</td>", date.day)),
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n");
+        html
+    }
+
+    fn title(&self) -> String {
+        format!("{} {}", MONTH_NAMES[self.month as usize - 1], self.year)
+    }
+
+    /// Title, weekday header and weeks, padded to the full width and to six weeks when in a year
+    fn text_lines(&self, mark: &dyn Fn(&Date) -> bool, padded: bool) -> Vec<String> {
+        let mut lines = vec![center(&self.title(), MONTH_WIDTH)];
+        lines.push(self.weekdays().iter().map(|d| header(*d)).collect::<Vec<_>>().join(" "));
+
+        let weeks = self.weeks();
+        for week in weeks.iter() {
+            let mut line = String::with_capacity(3 * 7);
+            for day in week.iter() {
+                match *day {
+                    Some(ref date) => line.push_str(&format!("{:>2}{}", date.day, if mark(date) { '*' } else { ' ' })),
+                    None => line.push_str("   "),
+                }
+            }
+            lines.push(line);
+        }
+
+        if padded {
+            lines.extend((weeks.len()..MAX_WEEKS).map(|_| String::new()));
+            lines.iter().map(|l| format!("{:<width$}", l, width = MONTH_WIDTH + 1)).collect()
+        } else {
+            lines.iter().map(|l| l.trim_end().to_string()).collect()
+        }
+    }
+}
+
+impl fmt::Display for MonthView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+/// The twelve months of a year, three abreast in text
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct YearView {
+    year: i32,
+    first_weekday: DayOfWeek,
+}
+
+impl YearView {
+
+    /// The given year, with weeks starting on Sunday
+    pub fn new(year: i32) -> Result<YearView, DateError> {
+        MonthView::new(1, year).map(|m| YearView { year: m.year, first_weekday: m.first_weekday })
+    }
+
+    /// The same year with weeks starting on the given day
+    pub fn with_first_weekday(self, first_weekday: DayOfWeek) -> YearView {
+        YearView { first_weekday, ..self }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn months(&self) -> Vec<MonthView> {
+        (1..13).map(|month| MonthView { month, year: self.year, first_weekday: self.first_weekday }).collect()
+    }
+
+    /// Renders as plain text, like `cal -y`
+    pub fn to_text(&self) -> String {
+        self.to_text_marking(|_| false)
+    }
+
+    /// Renders as plain text, with a `*` after the days to mark
+    pub fn to_text_marking<F: Fn(&Date) -> bool>(&self, mark: F) -> String {
+        let months = self.months();
+        // Three months with two spaces between them
+        let mut text = center(&self.year.to_string(), 3 * MONTH_WIDTH + 4).trim_end().to_string() + "\n\n";
+
+        for row in months.chunks(3) {
+            let blocks: Vec<Vec<String>> = row.iter().map(|m| m.text_lines(&mark, true)).collect();
+            for line in 0..blocks[0].len() {
+                let joined: Vec<&str> = blocks.iter().map(|b| b[line].as_str()).collect();
+                text.push_str(joined.join(" ").trim_end());
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    /// Renders as an HTML table with a month table in each cell, three abreast
+    pub fn to_html(&self) -> String {
+        self.to_html_marking(|_| false)
+    }
+
+    /// Renders as an HTML table with a month table in each cell, with a `marked` class on the cells of the days to mark
+    pub fn to_html_marking<F: Fn(&Date) -> bool>(&self, mark: F) -> String {
+        let mut html = format!("<table class=\"year\">\n<caption>{}</caption>\n", self.year);
+        for row in self.months().chunks(3) {
+            html.push_str("<tr>\n");
+            for month in row {
+                html.push_str("<td>\n");
+                html.push_str(&month.to_html_marking(&mark));
+                html.push_str("</td>\n");
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        html
+    }
+}
+
+impl fmt::Display for YearView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+/// Two letter weekday column header
+fn header(day: DayOfWeek) -> &'static str {
+    &day.abbreviation(Language::English)[..2]
+}
+
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.len());
+    format!("{}{}{}", " ".repeat(padding / 2), text, " ".repeat(padding - padding / 2))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn month_as_text() {
+        let february = MonthView::new(2, 2009).unwrap();
+
+        assert_eq!(february.to_text(), [
+            "   February 2009",
+            "Su Mo Tu We Th Fr Sa",
+            " 1  2  3  4  5  6  7",
+            " 8  9 10 11 12 13 14",
+            "15 16 17 18 19 20 21",
+            "22 23 24 25 26 27 28",
+            "",
+        ].join("\n"));
+        assert_eq!(february.with_first_weekday(DayOfWeek::Monday).to_text(), [
+            "   February 2009",
+            "Mo Tu We Th Fr Sa Su",
+            "                   1",
+            " 2  3  4  5  6  7  8",
+            " 9 10 11 12 13 14 15",
+            "16 17 18 19 20 21 22",
+            "23 24 25 26 27 28",
+            "",
+        ].join("\n"));
+        assert_eq!(MonthView::new(13, 2009), Err(DateError::BadMonth(13)));
+    }
+
+    #[test]
+    fn weeks_cover_the_month() {
+        let view = MonthView::of(Date::from_without_weekday(15, 8, 2015)).with_first_weekday(DayOfWeek::Monday);
+        let weeks = view.weeks();
+
+        assert_eq!(view.weekdays()[6], DayOfWeek::Sunday);
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks.iter().flat_map(|w| w.iter()).filter_map(|d| *d).count(), 31);
+        for week in weeks.iter() {
+            for (column, day) in week.iter().enumerate() {
+                if let Some(date) = *day {
+                    assert_eq!(date.day_of_week, view.weekdays()[column]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn marked_days_in_text_and_html() {
+        let sundays = |d: &Date| d.day == 1 && d.day_of_week == DayOfWeek::Sunday;
+        let year = YearView::new(1901).unwrap().to_text_marking(sundays);
+
+        assert_eq!(year.matches('*').count(), 2);
+        assert!(year.contains(" 1* 2  3"));
+        assert_eq!(year.lines().next(), Some("                              1901"));
+        assert_eq!(year.lines().count(), 2 + 4 * 8);
+
+        let html = MonthView::new(9, 1901).unwrap().to_html_marking(sundays);
+        assert!(html.starts_with("<table class=\"month\">\n<caption>September 1901</caption>\n<thead><tr><th title=\"Sunday\">Su</th>"));
+        assert!(html.contains("<tr><td class=\"marked\">1</td><td>2</td>"));
+        assert_eq!(html.matches("<tr>").count(), 1 + 5);
+        assert_eq!(YearView::new(1901).unwrap().to_html().matches("<table class=\"month\">").count(), 12);
+    }
+}
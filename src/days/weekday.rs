@@ -0,0 +1,205 @@
+use days::{DayOfWeek, WEEKDAYS, WEEKDAY_NAMES};
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+/// A language to name weekdays in
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+const LANGUAGES: [Language; 4] = [Language::English, Language::French, Language::German, Language::Spanish];
+
+const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const FRENCH_NAMES: [&str; 7] = ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"];
+
+const FRENCH_ABBREVIATIONS: [&str; 7] = ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."];
+
+const GERMAN_NAMES: [&str; 7] = ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"];
+
+const GERMAN_ABBREVIATIONS: [&str; 7] = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"];
+
+const SPANISH_NAMES: [&str; 7] = ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"];
+
+const SPANISH_ABBREVIATIONS: [&str; 7] = ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"];
+
+impl Language {
+
+    pub fn all() -> &'static [Language] {
+        &LANGUAGES
+    }
+
+    /// Full weekday names from Monday, capitalized as within a sentence
+    pub fn weekday_names(&self) -> &'static [&'static str; 7] {
+        match *self {
+            Language::English => &WEEKDAY_NAMES,
+            Language::French => &FRENCH_NAMES,
+            Language::German => &GERMAN_NAMES,
+            Language::Spanish => &SPANISH_NAMES,
+        }
+    }
+
+    /// Abbreviated weekday names from Monday, in the customary form of the language
+    pub fn weekday_abbreviations(&self) -> &'static [&'static str; 7] {
+        match *self {
+            Language::English => &WEEKDAY_ABBREVIATIONS,
+            Language::French => &FRENCH_ABBREVIATIONS,
+            Language::German => &GERMAN_ABBREVIATIONS,
+            Language::Spanish => &SPANISH_ABBREVIATIONS,
+        }
+    }
+}
+
+impl DayOfWeek {
+
+    /// Days from this one to the next other, 0 if they are the same
+    pub fn days_until(&self, other: DayOfWeek) -> u8 {
+        (other as i64 - *self as i64).rem_euclid(7) as u8
+    }
+
+    /// ISO-8601 numbering, from 1 for Monday to 7 for Sunday
+    pub fn iso_number(&self) -> u8 {
+        *self as u8 + 1
+    }
+
+    pub fn from_iso_number(number: u8) -> Option<DayOfWeek> {
+        if number >= 1 && number <= 7 { Some(WEEKDAYS[number as usize - 1]) } else { None }
+    }
+
+    /// US numbering, from 0 for Sunday to 6 for Saturday
+    pub fn us_number(&self) -> u8 {
+        self.iso_number() % 7
+    }
+
+    pub fn from_us_number(number: u8) -> Option<DayOfWeek> {
+        if number <= 6 { Some(WEEKDAYS[(number as usize + 6) % 7]) } else { None }
+    }
+
+    pub fn name(&self, language: Language) -> &'static str {
+        language.weekday_names()[*self as usize]
+    }
+
+    pub fn abbreviation(&self, language: Language) -> &'static str {
+        language.weekday_abbreviations()[*self as usize]
+    }
+
+    /// The weekday with the given full or abbreviated name in the language, ignoring case
+    /// and, for abbreviations, a trailing `.`
+    pub fn from_name(name: &str, language: Language) -> Result<DayOfWeek, ParseWeekdayError> {
+        let lowercase = name.trim().to_lowercase();
+        let abbreviation = lowercase.trim_end_matches('.');
+        let matches = |names: &[&str; 7], candidate: &str| {
+            names.iter().position(|n| n.to_lowercase().trim_end_matches('.') == candidate)
+        };
+
+        matches(language.weekday_names(), &lowercase)
+            .or_else(|| matches(language.weekday_abbreviations(), abbreviation))
+            .map(|ix| WEEKDAYS[ix])
+            .ok_or_else(|| ParseWeekdayError(name.trim().to_string()))
+    }
+}
+
+/// The day the given number of days later, wrapping around the week
+impl Add<i64> for DayOfWeek {
+    type Output = DayOfWeek;
+
+    fn add(self, days: i64) -> DayOfWeek {
+        WEEKDAYS[((self as i64 + days.rem_euclid(7)) % 7) as usize]
+    }
+}
+
+impl Sub<i64> for DayOfWeek {
+    type Output = DayOfWeek;
+
+    fn sub(self, days: i64) -> DayOfWeek {
+        self + (7 - days.rem_euclid(7))
+    }
+}
+
+/// The English name
+impl fmt::Display for DayOfWeek {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name(Language::English))
+    }
+}
+
+/// A name that is no weekday
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseWeekdayError(pub String);
+
+impl fmt::Display for ParseWeekdayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown weekday {:?}", self.0)
+    }
+}
+
+impl Error for ParseWeekdayError {}
+
+/// Parses English full or abbreviated names, like `Monday` or `mon`
+impl FromStr for DayOfWeek {
+    type Err = ParseWeekdayError;
+
+    fn from_str(s: &str) -> Result<DayOfWeek, ParseWeekdayError> {
+        DayOfWeek::from_name(s, Language::English)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn weekday_arithmetic() {
+        assert_eq!(DayOfWeek::Sunday.succ(), DayOfWeek::Monday);
+        assert_eq!(DayOfWeek::Monday.pred(), DayOfWeek::Sunday);
+        assert_eq!(DayOfWeek::Friday + 3, DayOfWeek::Monday);
+        assert_eq!(DayOfWeek::Friday - 12, DayOfWeek::Sunday);
+        assert_eq!(DayOfWeek::Saturday.days_until(DayOfWeek::Monday), 2);
+        assert_eq!(DayOfWeek::Monday.days_until(DayOfWeek::Monday), 0);
+
+        for &day in WEEKDAYS.iter() {
+            assert_eq!(day.succ().pred(), day);
+            assert_eq!(day + day.days_until(DayOfWeek::Thursday) as i64, DayOfWeek::Thursday);
+        }
+    }
+
+    #[test]
+    fn weekday_numbering() {
+        assert_eq!(DayOfWeek::Monday.iso_number(), 1);
+        assert_eq!(DayOfWeek::Sunday.iso_number(), 7);
+        assert_eq!(DayOfWeek::Sunday.us_number(), 0);
+        assert_eq!(DayOfWeek::Saturday.us_number(), 6);
+        assert_eq!(DayOfWeek::from_iso_number(0), None);
+        assert_eq!(DayOfWeek::from_us_number(7), None);
+
+        for &day in WEEKDAYS.iter() {
+            assert_eq!(DayOfWeek::from_iso_number(day.iso_number()), Some(day));
+            assert_eq!(DayOfWeek::from_us_number(day.us_number()), Some(day));
+        }
+    }
+
+    #[test]
+    fn weekday_names() {
+        assert_eq!("Wednesday".parse(), Ok(DayOfWeek::Wednesday));
+        assert_eq!(" sat ".parse(), Ok(DayOfWeek::Saturday));
+        assert_eq!("Thurs".parse::<DayOfWeek>(), Err(ParseWeekdayError("Thurs".to_string())));
+        assert_eq!(DayOfWeek::from_name("Dim", Language::French), Ok(DayOfWeek::Sunday));
+        assert_eq!(DayOfWeek::from_name("MIÉRCOLES", Language::Spanish), Ok(DayOfWeek::Wednesday));
+        assert_eq!(DayOfWeek::Thursday.name(Language::German), "Donnerstag");
+        assert_eq!(DayOfWeek::Friday.to_string(), "Friday");
+
+        for &language in Language::all() {
+            for &day in WEEKDAYS.iter() {
+                assert_eq!(DayOfWeek::from_name(day.name(language), language), Ok(day));
+                assert_eq!(DayOfWeek::from_name(day.abbreviation(language), language), Ok(day));
+            }
+        }
+    }
+}
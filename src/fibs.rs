@@ -1,37 +1,96 @@
 
+use ints::{natural, ArithmeticError, CheckedNatural, Natural};
+use num::{BigUint, Bounded};
+use std::mem;
+
 // 2
 
-pub fn sum_even_fibs(up_to: u32) -> u32 {
-    let fibs = Fib::start_from(1, 2).take_while(|x| *x < up_to);
+pub fn sum_even_fibs<N: Natural>(up_to: N) -> N {
+    let fibs = Fib::start_from(N::one(), natural(2)).take_while(|x| *x < up_to);
+
+    let even_fibs = fibs.filter(|x| x.is_even());
+
+    even_fibs.fold(N::zero(), |x, y| x + y)
+}
+
+/// Sum of the even Fibonacci numbers below up_to, failing instead of overflowing
+pub fn checked_sum_even_fibs<N: CheckedNatural>(up_to: N) -> Result<N, ArithmeticError> {
+    let mut sum = N::zero();
+    let mut a = N::one();
+    // None once the next Fibonacci number no longer fits in N, so is past up_to anyway
+    let mut b = Some(natural(2));
+
+    while a < up_to {
+        if a.is_even() {
+            sum = sum.checked_add(&a).ok_or(ArithmeticError::Overflow)?;
+        }
+        match b {
+            Some(next) => {
+                b = a.checked_add(&next);
+                a = next;
+            },
+            None => break,
+        }
+    }
+
+    Ok(sum)
+}
+
+/// Sum of the even Fibonacci numbers below up_to, or the type's maximum value if it overflows
+pub fn saturating_sum_even_fibs<N: CheckedNatural + Bounded>(up_to: N) -> N {
+    checked_sum_even_fibs(up_to).unwrap_or_else(|_| N::max_value())
+}
 
-    let even_fibs = fibs.filter(|x| x % 2 == 0);
+// 25
 
-    even_fibs.fold(0, |x, y| x + y)
+/// Index of the first Fibonacci number with the given count of decimal digits
+pub fn first_fib_index_with_digits(digits: usize) -> usize {
+    let fibs = Fib::start_from(BigUint::from(1u64), BigUint::from(1u64));
+    fibs.zip(1..).find(|&(ref f, _)| f.to_string().len() >= digits).unwrap().1
 }
 
-struct Fib {a: u32, b: u32}
+pub struct Fib<N> {a: N, b: N}
 
-impl Fib {
-    fn start_from(a: u32, b: u32) -> Fib {
+impl<N: Natural> Fib<N> {
+    pub fn start_from(a: N, b: N) -> Fib<N> {
         Fib {a: a, b: b}
     }
 }
 
-impl Iterator for Fib {
+impl<N: Natural> Iterator for Fib<N> {
 
-    type Item = u32;
+    type Item = N;
 
-    fn next(&mut self) -> Option<u32> {
+    fn next(&mut self) -> Option<N> {
 
-        let next_val = self.a;
-        let new_b = match (&self.a, &self.b) {
-            (a, b) => a + b
-        };
+        let new_b = self.a.clone() + self.b.clone();
 
-        self.a = self.b;
-        self.b = new_b;
+        let next_val = mem::replace(&mut self.a, mem::replace(&mut self.b, new_b));
 
         Some(next_val)
     }
 }
 
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn fibs_do_not_overflow_with_big_integers() {
+        let fib_100 = Fib::start_from(BigUint::from(1u64), BigUint::from(1u64)).nth(99).unwrap();
+
+        assert_eq!(fib_100.to_string(), "354224848179261915075");
+        assert_eq!(sum_even_fibs(100u32), 44);
+        assert_eq!(first_fib_index_with_digits(3), 12);
+    }
+
+    #[test]
+    fn checked_sum_does_not_overflow_near_the_maximum() {
+        assert_eq!(checked_sum_even_fibs(4000000u32), Ok(4613732));
+        assert_eq!(checked_sum_even_fibs(u8::max_value()), Ok(188));
+        assert_eq!(checked_sum_even_fibs(u64::max_value()), Ok(15970217317495049952));
+        assert_eq!(saturating_sum_even_fibs(4000000u32), 4613732);
+        assert_eq!(saturating_sum_even_fibs(u64::max_value()), 15970217317495049952);
+    }
+}
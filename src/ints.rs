@@ -1,6 +1,56 @@
 
+use num;
+use num::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, FromPrimitive, Integer, ToPrimitive, Unsigned};
 use primes;
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Unsigned integers the number-theory helpers are generic over, either primitive
+/// or arbitrary-precision (`num::BigUint`) for results that would overflow the primitive ones
+pub trait Natural: Integer + Unsigned + FromPrimitive + ToPrimitive + Clone {}
+
+impl<N> Natural for N where N: Integer + Unsigned + FromPrimitive + ToPrimitive + Clone {}
+
+/// Natural types supporting arithmetic that reports overflow instead of panicking or wrapping
+pub trait CheckedNatural: Natural + CheckedAdd + CheckedMul {}
+
+impl<N> CheckedNatural for N where N: Natural + CheckedAdd + CheckedMul {}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ArithmeticError {
+    /// The result does not fit in the integer type
+    Overflow,
+    DivisionByZero,
+    /// The arguments are outside of the function's domain
+    InvalidInput(&'static str),
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArithmeticError::Overflow => write!(f, "arithmetic overflow"),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+            ArithmeticError::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
+
+fn checked_mul<N: CheckedNatural>(a: &N, b: &N) -> Result<N, ArithmeticError> {
+    a.checked_mul(b).ok_or(ArithmeticError::Overflow)
+}
+
+/// Quotient of a by b, failing if b is zero
+pub fn checked_div<N: Natural + CheckedDiv>(a: &N, b: &N) -> Result<N, ArithmeticError> {
+    a.checked_div(b).ok_or(ArithmeticError::DivisionByZero)
+}
+
+/// Converts a u64 to any natural type, panicking if it does not fit
+pub fn natural<N: Natural>(n: u64) -> N {
+    N::from_u64(n).expect("Value does not fit in the integer type")
+}
 
 fn is_divisible_by(lhs: u64, rhs: u64) -> bool {
     lhs % rhs == 0
@@ -31,8 +81,19 @@ pub fn smallest_number_divisible_by_ns_from_1_to_n__fast(n: u64, ps: &mut primes
     least_common_multiple((1..n+1).collect(), ps)
 }
 
+/// Greatest common divisor by Euclid's algorithm
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
 /// http://mathforum.org/library/drmath/view/62527.html
-pub fn least_common_multiple(ns: Vec<u64>, ps: &mut primes::CachedPrimes) -> u64 {
+///
+/// Zero if any of ns is zero, as zero is then the only common multiple
+pub fn least_common_multiple<N: Natural>(ns: Vec<u64>, ps: &mut primes::CachedPrimes) -> N {
+
+    if ns.contains(&0) {
+        return N::zero();
+    }
 
     let mut factors_and_exponents: HashMap<u64, u32> = HashMap::new();
 
@@ -45,14 +106,49 @@ pub fn least_common_multiple(ns: Vec<u64>, ps: &mut primes::CachedPrimes) -> u64
         }
     }
 
-    let mut result = 1;
+    let mut result = N::one();
     for (factor, exponent) in factors_and_exponents {
-        result *= factor.pow(exponent);
+        result = result * num::pow(natural(factor), exponent as usize);
     }
 
     result
 }
 
+/// Least common multiple of ns, failing on overflow. Zero if any of ns is zero, like `least_common_multiple`
+pub fn checked_least_common_multiple<N: CheckedNatural>(ns: Vec<u64>, ps: &mut primes::CachedPrimes) -> Result<N, ArithmeticError> {
+
+    if ns.contains(&0) {
+        return Ok(N::zero());
+    }
+
+    let mut factors_and_exponents: HashMap<u64, u32> = HashMap::new();
+
+    for n in ns {
+        for (factor, count) in primes::factorize_unique(n, ps) {
+            let exponent = factors_and_exponents.entry(factor).or_insert(0);
+            *exponent = (*exponent).max(count);
+        }
+    }
+
+    let mut result = N::one();
+    for (factor, exponent) in factors_and_exponents {
+        let factor: N = N::from_u64(factor).ok_or(ArithmeticError::Overflow)?;
+        let power = num::checked_pow(factor, exponent as usize).ok_or(ArithmeticError::Overflow)?;
+        result = checked_mul(&result, &power)?;
+    }
+
+    Ok(result)
+}
+
+/// Least common multiple of ns, or the type's maximum value if it overflows. Zero if any of ns is zero
+pub fn saturating_least_common_multiple<N: CheckedNatural + Bounded>(ns: Vec<u64>, ps: &mut primes::CachedPrimes) -> N {
+    match checked_least_common_multiple(ns, ps) {
+        Ok(lcm) => lcm,
+        Err(ArithmeticError::Overflow) => N::max_value(),
+        Err(e) => panic!("Least common multiple failed: {}", e),
+    }
+}
+
 pub struct PythagoreanTriplets {
     a: u32,
     b: u32,
@@ -143,18 +239,152 @@ pub fn divisor_count(n: u64, ps: &mut primes::CachedPrimes) -> u32 {
         .product()
 }
 
-pub fn factorial(n: u64) -> u64 {
-    if n == 0 || n == 1 {
-        1
+pub fn factorial<N: Natural>(n: N) -> N {
+    if n <= N::one() {
+        N::one()
     } else {
-        n * factorial(n-1)
+        n.clone() * factorial(n - N::one())
     }
 }
 
-pub fn combination(n: u64, k: u64) -> u64 {
-    let num: u64 = (k+2..n).product();
-    let denom: u64 = (2..k).product();
-    num / denom
+pub fn checked_factorial<N: CheckedNatural>(n: N) -> Result<N, ArithmeticError> {
+    let mut result = N::one();
+    let mut i = N::one();
+    while i < n {
+        i = i + N::one();
+        result = checked_mul(&result, &i)?;
+    }
+    Ok(result)
+}
+
+/// Factorial of n, or the type's maximum value if it overflows
+pub fn saturating_factorial<N: CheckedNatural + Bounded>(n: N) -> N {
+    checked_factorial(n).unwrap_or_else(|_| N::max_value())
+}
+
+// 15
+
+/// Number of ways to choose k elements out of n, same as `binomial`
+pub fn combination<N: Natural>(n: N, k: N) -> N {
+    binomial(n, k)
+}
+
+/// Same as `checked_binomial` but rejects choosing more elements than available
+pub fn checked_combination<N: CheckedNatural>(n: N, k: N) -> Result<N, ArithmeticError> {
+    if k > n {
+        return Err(ArithmeticError::InvalidInput("cannot choose more elements than available"));
+    }
+    checked_binomial(n, k)
+}
+
+/// Number of ways to choose k elements out of n, or the type's maximum value if it overflows.
+/// Zero when k > n, like `combination`
+pub fn saturating_combination<N: CheckedNatural + Bounded>(n: N, k: N) -> N {
+    checked_binomial(n, k).unwrap_or_else(|_| N::max_value())
+}
+
+/// Binomial coefficient C(n, k), zero when k > n
+///
+/// Computed as C(n-k+i, i) for i in 1..k, cancelling the gcd before each step so
+/// intermediate values never exceed the result
+pub fn binomial<N: Natural>(n: N, k: N) -> N {
+    checked_binomial_by(n, k, |a, b| Some(a * b)).expect("Unchecked multiplication never fails")
+}
+
+/// Binomial coefficient C(n, k), failing if it does not fit in N
+pub fn checked_binomial<N: CheckedNatural>(n: N, k: N) -> Result<N, ArithmeticError> {
+    checked_binomial_by(n, k, |a, b| a.checked_mul(&b)).ok_or(ArithmeticError::Overflow)
+}
+
+fn checked_binomial_by<N: Natural, F: Fn(N, N) -> Option<N>>(n: N, k: N, mul: F) -> Option<N> {
+    if k > n {
+        return Some(N::zero());
+    }
+    let n_minus_k = n.clone() - k.clone();
+    let (base, k) = if k > n_minus_k { (k, n_minus_k) } else { (n_minus_k, k) };
+
+    let mut result = N::one();
+    let mut i = N::one();
+    while i <= k {
+        // result * (base + i) is divisible by i, and once the gcd is cancelled out of
+        // result, what's left of i divides base + i
+        let g = result.gcd(&i);
+        let factor = (base.clone() + i.clone()) / (i.clone() / g.clone());
+        result = mul(result / g, factor)?;
+        i = i + N::one();
+    }
+    Some(result)
+}
+
+/// Binomial coefficient C(n, k) modulo the prime p, by Lucas' theorem, failing unless p is prime
+pub fn binomial_mod_prime(n: u64, k: u64, p: u64) -> Result<u64, ArithmeticError> {
+    if !primes::is_prime(p) {
+        return Err(ArithmeticError::InvalidInput("modulus must be prime"));
+    }
+    let mut result = 1;
+    let (mut n, mut k) = (n, k);
+    while k > 0 {
+        let (n_digit, k_digit) = (n % p, k % p);
+        if k_digit > n_digit {
+            return Ok(0);
+        }
+        result = primes::mul_mod(result, small_binomial_mod_prime(n_digit, k_digit, p), p);
+        n /= p;
+        k /= p;
+    }
+    Ok(result)
+}
+
+/// C(n, k) modulo the prime p where k <= n < p, dividing by Fermat's little theorem
+fn small_binomial_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    let k = k.min(n - k);
+    let mut num = 1;
+    let mut denom = 1;
+    for i in 0..k {
+        num = primes::mul_mod(num, n - i, p);
+        denom = primes::mul_mod(denom, i + 1, p);
+    }
+    primes::mul_mod(num, primes::pow_mod(denom, p - 2, p), p)
+}
+
+/// Rows of Pascal's triangle, starting from [1]
+pub struct PascalRows<N> {
+    next: Vec<N>,
+}
+
+impl<N: Natural> PascalRows<N> {
+    pub fn new() -> PascalRows<N> {
+        PascalRows { next: vec![N::one()] }
+    }
+}
+
+impl<N: Natural> Iterator for PascalRows<N> {
+    type Item = Vec<N>;
+
+    fn next(&mut self) -> Option<Vec<N>> {
+        let mut following = Vec::with_capacity(self.next.len() + 1);
+        following.push(N::one());
+        for pair in self.next.windows(2) {
+            following.push(pair[0].clone() + pair[1].clone());
+        }
+        following.push(N::one());
+        Some(::std::mem::replace(&mut self.next, following))
+    }
+}
+
+// 20
+
+/// Sum of the decimal digits of n
+pub fn digit_sum<N: Natural>(n: N) -> u64 {
+    let ten: N = natural(10);
+    let mut sum = 0;
+    let mut rest = n;
+    while !rest.is_zero() {
+        let (quotient, digit) = rest.div_rem(&ten);
+        sum += digit.to_u64().expect("A digit always fits in u64");
+        rest = quotient;
+    }
+    sum
 }
 
 #[cfg(test)]
@@ -183,4 +413,81 @@ mod test {
         assert_eq!(divisor_count(21, &mut ps), 4);
         assert_eq!(divisor_count(28, &mut ps), 6);
     }
+
+    #[test]
+    fn big_integer_factorial_and_lcm() {
+        let mut ps = primes::CachedPrimes::new();
+
+        assert_eq!(factorial(20u64), 2432902008176640000);
+        assert_eq!(factorial(num::BigUint::from(25u64)).to_string(), "15511210043330985984000000");
+        assert_eq!(digit_sum(factorial(num::BigUint::from(100u64))), 648);
+
+        let lcm_20: u64 = least_common_multiple((1..21).collect(), &mut ps);
+        let lcm_100: num::BigUint = least_common_multiple((1..101).collect(), &mut ps);
+        assert_eq!(lcm_20, 232792560);
+        assert_eq!(lcm_100.to_string(), "69720375229712477164533808935312303556800");
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_errors() {
+        let mut ps = primes::CachedPrimes::new();
+
+        assert_eq!(checked_factorial(0u64), Ok(1));
+        assert_eq!(checked_factorial(20u64), Ok(2432902008176640000));
+        assert_eq!(checked_factorial(21u64), Err(ArithmeticError::Overflow));
+        assert!(checked_factorial(num::BigUint::from(21u64)).is_ok());
+        assert_eq!(saturating_factorial(21u64), u64::max_value());
+
+        assert!(checked_combination(3u64, 5u64).is_err());
+        assert_eq!(checked_combination(100u64, 2u64), Ok(4950));
+        assert_eq!(checked_combination(68u64, 34u64), Err(ArithmeticError::Overflow));
+        assert_eq!(saturating_combination(68u64, 34u64), u64::max_value());
+        assert_eq!(saturating_combination(10u8, 3u8), 120);
+        assert_eq!(saturating_combination(3u8, 5u8), 0);
+        assert_eq!(checked_div(&7u32, &2u32), Ok(3));
+        assert_eq!(checked_div(&7u32, &0u32), Err(ArithmeticError::DivisionByZero));
+
+        assert_eq!(checked_least_common_multiple::<u32>((1..21).collect(), &mut ps), Ok(232792560));
+        assert_eq!(checked_least_common_multiple::<u32>((1..41).collect(), &mut ps), Err(ArithmeticError::Overflow));
+        assert_eq!(checked_least_common_multiple::<u64>(vec![0, 2], &mut ps), Ok(0));
+        assert_eq!(least_common_multiple::<u64>(vec![3, 0, 2], &mut ps), 0);
+        assert_eq!(saturating_least_common_multiple::<u64>(vec![0, 3], &mut ps), 0);
+        assert_eq!(saturating_least_common_multiple::<u8>((1..11).collect(), &mut ps), 255);
+    }
+
+    #[test]
+    fn binomials_are_correct() {
+        assert_eq!(binomial(0u64, 0u64), 1);
+        assert_eq!(binomial(5u64, 2u64), 10);
+        assert_eq!(binomial(5u64, 3u64), 10);
+        assert_eq!(binomial(3u64, 5u64), 0);
+        assert_eq!(combination(40u64, 20u64), 137846528820);
+        assert_eq!(binomial(67u64, 33u64), 14226520737620288370);
+        assert_eq!(checked_binomial(62u32, 31u32), Err(ArithmeticError::Overflow));
+        assert_eq!(binomial(num::BigUint::from(100u64), num::BigUint::from(50u64)).to_string(),
+                   "100891344545564193334812497256");
+    }
+
+    #[test]
+    fn binomials_modulo_a_prime() {
+        assert_eq!(binomial_mod_prime(1000, 300, 13), Ok(10));
+        assert_eq!(binomial_mod_prime(1000000, 1234, 1000003), Ok(763230));
+        assert_eq!(binomial_mod_prime(123456789, 98765, 101), Ok(0));
+        assert_eq!(binomial_mod_prime(3, 5, 7), Ok(0));
+        assert_eq!(binomial_mod_prime(5, 0, 2), Ok(1));
+        for &p in [0, 1, 4, 1000001].iter() {
+            assert_eq!(binomial_mod_prime(5, 2, p), Err(ArithmeticError::InvalidInput("modulus must be prime")));
+        }
+    }
+
+    #[test]
+    fn pascal_rows_match_binomials() {
+        let rows: Vec<Vec<u64>> = PascalRows::new().take(5).collect();
+        assert_eq!(rows, vec![vec![1], vec![1, 1], vec![1, 2, 1], vec![1, 3, 3, 1], vec![1, 4, 6, 4, 1]]);
+
+        let row_30 = PascalRows::<u64>::new().nth(30).unwrap();
+        for (k, entry) in row_30.into_iter().enumerate() {
+            assert_eq!(entry, binomial(30, k as u64));
+        }
+    }
 }
@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate enum_traits_macros;
+extern crate enum_traits;
+extern crate num;
+
+pub mod days;
+pub mod fibs;
+pub mod ints;
+pub mod matrix;
+pub mod nonempty;
+pub mod numbers;
+pub mod palindromes;
+pub mod primes;
+pub mod problems;
+pub mod sequences;
+pub mod subseq;
+pub mod triangles;
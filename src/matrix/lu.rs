@@ -0,0 +1,363 @@
+use matrix::ops::MatrixError;
+use matrix::DenseMatrix;
+use num::rational::Ratio;
+use num::{Integer, Signed};
+use std::f64;
+
+/// A square matrix A factored as PA = LU with partial pivoting, where P permutes the rows of A,
+/// L is lower triangular with ones on the diagonal and U is upper triangular
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuDecomposition {
+    /// L below the diagonal, leaving out its ones, and U on and above it
+    lu: DenseMatrix<f64>,
+    /// The row of A in each row of PA
+    permutation: Vec<usize>,
+    /// Whether P swaps an odd number of rows
+    odd: bool,
+    /// Pivots this close to zero make the matrix singular
+    tolerance: f64,
+}
+
+impl LuDecomposition {
+
+    pub fn lower(&self) -> DenseMatrix<f64> {
+        let n = self.lu.row_count;
+        let mut lower = DenseMatrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                lower[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        lower
+    }
+
+    pub fn upper(&self) -> DenseMatrix<f64> {
+        let n = self.lu.row_count;
+        let mut upper = DenseMatrix::zeros(n, n);
+        for i in 0..n {
+            for j in i..n {
+                upper[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        upper
+    }
+
+    /// The row of the original matrix that ended up in each row
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn is_singular(&self) -> bool {
+        (0..self.lu.row_count).any(|i| self.lu[(i, i)].abs() <= self.tolerance)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let product: f64 = (0..self.lu.row_count).map(|i| self.lu[(i, i)]).product();
+        if self.odd { -product } else { product }
+    }
+
+    /// The x for which Ax = b
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let n = self.lu.row_count;
+        if b.len() != n {
+            return Err(MatrixError::DimensionMismatch { left: (n, n), right: (b.len(), 1) });
+        }
+        if self.is_singular() {
+            return Err(MatrixError::Singular);
+        }
+
+        // Ly = Pb, then Ux = y
+        let mut x: Vec<f64> = self.permutation.iter().map(|&row| b[row]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] -= self.lu[(i, j)] * x[j];
+            }
+            x[i] /= self.lu[(i, i)];
+        }
+        Ok(x)
+    }
+}
+
+/// Entries this many times the largest one, or less, count as zero in elimination
+fn tolerance(m: &DenseMatrix<f64>) -> f64 {
+    let largest = m.ns.iter().fold(0.0f64, |max, n| max.max(n.abs()));
+    largest * m.row_count.max(m.col_count) as f64 * f64::EPSILON
+}
+
+impl DenseMatrix<f64> {
+
+    /// LU decomposition with partial pivoting, failing unless the matrix is square
+    pub fn lu(&self) -> Result<LuDecomposition, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare(self.row_count, self.col_count));
+        }
+        let n = self.row_count;
+        let mut lu = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut odd = false;
+        let tolerance = tolerance(self);
+
+        for k in 0..n {
+            // Total ordering puts NaN above every number, so NaN entries propagate rather than panic
+            let pivot_row = (k..n).max_by(|&a, &b| lu[(a, k)].abs().total_cmp(&lu[(b, k)].abs())).unwrap_or(k);
+            if pivot_row != k {
+                lu.swap_rows(k, pivot_row);
+                permutation.swap(k, pivot_row);
+                odd = !odd;
+            }
+            let pivot = lu[(k, k)];
+            if pivot.abs() <= tolerance {
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = lu[(i, k)] / pivot;
+                lu[(i, k)] = factor;
+                for j in k + 1..n {
+                    lu[(i, j)] -= factor * lu[(k, j)];
+                }
+            }
+        }
+
+        Ok(LuDecomposition { lu, permutation, odd, tolerance })
+    }
+
+    pub fn determinant(&self) -> Result<f64, MatrixError> {
+        self.lu().map(|lu| lu.determinant())
+    }
+
+    /// The x for which Ax = b, with A this matrix
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        self.lu()?.solve(b)
+    }
+
+    pub fn inverse(&self) -> Result<DenseMatrix<f64>, MatrixError> {
+        let lu = self.lu()?;
+        let n = self.row_count;
+        let mut inverse = DenseMatrix::zeros(n, n);
+        let mut unit = vec![0.0; n];
+        for col in 0..n {
+            unit[col] = 1.0;
+            for (row, x) in lu.solve(&unit)?.into_iter().enumerate() {
+                inverse[(row, col)] = x;
+            }
+            unit[col] = 0.0;
+        }
+        Ok(inverse)
+    }
+
+    /// Number of linearly independent rows, by Gaussian elimination with partial pivoting
+    pub fn rank(&self) -> usize {
+        let mut m = self.clone();
+        let tolerance = tolerance(self);
+        let mut rank = 0;
+
+        for col in 0..m.col_count {
+            if rank == m.row_count {
+                break;
+            }
+            let pivot_row = (rank..m.row_count)
+                .max_by(|&a, &b| m[(a, col)].abs().total_cmp(&m[(b, col)].abs()))
+                .expect("Rows left");
+            if m[(pivot_row, col)].abs() <= tolerance {
+                continue;
+            }
+            m.swap_rows(rank, pivot_row);
+            for i in rank + 1..m.row_count {
+                let factor = m[(i, col)] / m[(rank, col)];
+                for j in col..m.col_count {
+                    m[(i, j)] -= factor * m[(rank, j)];
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+}
+
+impl<T: Clone + Integer + Signed> DenseMatrix<T> {
+
+    /// Exact determinant by fraction-free (Bareiss) elimination, where every division leaves no remainder
+    pub fn bareiss_determinant(&self) -> Result<T, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare(self.row_count, self.col_count));
+        }
+        let n = self.row_count;
+        if n == 0 {
+            return Ok(T::one());
+        }
+        let (echelon, rank, odd) = self.bareiss();
+        if rank < n {
+            return Ok(T::zero());
+        }
+        let last = echelon[(n - 1, n - 1)].clone();
+        Ok(if odd { -last } else { last })
+    }
+
+    /// Exact number of linearly independent rows
+    pub fn bareiss_rank(&self) -> usize {
+        self.bareiss().1
+    }
+
+    /// The exact rational x for which Ax = b, with A this matrix
+    pub fn solve_exact(&self, b: &[T]) -> Result<Vec<Ratio<T>>, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare(self.row_count, self.col_count));
+        }
+        let n = self.row_count;
+        if b.len() != n {
+            return Err(MatrixError::DimensionMismatch { left: (n, n), right: (b.len(), 1) });
+        }
+
+        let mut augmented = DenseMatrix::zeros(n, n + 1);
+        for i in 0..n {
+            for j in 0..n {
+                augmented[(i, j)] = self[(i, j)].clone();
+            }
+            augmented[(i, n)] = b[i].clone();
+        }
+        let (echelon, _, _) = augmented.bareiss();
+        if (0..n).any(|i| echelon[(i, i)].is_zero()) {
+            return Err(MatrixError::Singular);
+        }
+
+        let mut x: Vec<Ratio<T>> = vec![Ratio::from_integer(T::zero()); n];
+        for i in (0..n).rev() {
+            let mut sum = Ratio::from_integer(echelon[(i, n)].clone());
+            for j in i + 1..n {
+                sum = sum - x[j].clone() * echelon[(i, j)].clone();
+            }
+            x[i] = sum / echelon[(i, i)].clone();
+        }
+        Ok(x)
+    }
+
+    /// Row echelon form by fraction-free elimination, with its rank and whether an odd number of rows were swapped
+    fn bareiss(&self) -> (DenseMatrix<T>, usize, bool) {
+        let mut m = self.clone();
+        let mut previous_pivot = T::one();
+        let mut rank = 0;
+        let mut odd = false;
+
+        for col in 0..m.col_count {
+            if rank == m.row_count {
+                break;
+            }
+            let pivot_row = match (rank..m.row_count).find(|&i| !m[(i, col)].is_zero()) {
+                Some(row) => row,
+                None => continue,
+            };
+            if pivot_row != rank {
+                m.swap_rows(rank, pivot_row);
+                odd = !odd;
+            }
+            let pivot = m[(rank, col)].clone();
+            for i in rank + 1..m.row_count {
+                for j in col + 1..m.col_count {
+                    let n = m[(i, j)].clone() * pivot.clone() - m[(i, col)].clone() * m[(rank, j)].clone();
+                    m[(i, j)] = n / previous_pivot.clone();
+                }
+                m[(i, col)] = T::zero();
+            }
+            previous_pivot = pivot;
+            rank += 1;
+        }
+        (m, rank, odd)
+    }
+}
+
+impl<T> DenseMatrix<T> {
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.col_count {
+            self.ns.swap(a * self.col_count + col, b * self.col_count + col);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn grid(grid: &str) -> DenseMatrix<f64> {
+        DenseMatrix::from_grid(grid).expect("Failed to parse grid matrix")
+    }
+
+    fn assert_close(a: &DenseMatrix<f64>, b: &DenseMatrix<f64>) {
+        assert_eq!(a.dimensions(), b.dimensions());
+        for i in 0..a.row_count {
+            for j in 0..a.col_count {
+                assert!((a[(i, j)] - b[(i, j)]).abs() < 1e-9, "{} != {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn lu_with_partial_pivoting() {
+        let a = grid("2 1 1\n4 -6 0\n-2 7 2");
+        let lu = a.lu().unwrap();
+        let mut permuted = a.clone();
+        for (i, &row) in lu.permutation().iter().enumerate() {
+            for col in 0..3 {
+                permuted[(i, col)] = a[(row, col)];
+            }
+        }
+
+        assert_close(&lu.lower().checked_mul(&lu.upper()).unwrap(), &permuted);
+        assert_eq!(lu.permutation()[0], 1);
+        assert!((a.determinant().unwrap() + 16.0).abs() < 1e-9);
+        assert_eq!(a.transpose().lu().map(|lu| lu.is_singular()), Ok(false));
+        assert_eq!(grid("1 2 3\n4 5 6").lu(), Err(MatrixError::NotSquare(2, 3)));
+    }
+
+    #[test]
+    fn solves_and_inverses() {
+        let a = grid("2 1 1\n4 -6 0\n-2 7 2");
+        let x = a.solve(&[7.0, -8.0, 18.0]).unwrap();
+
+        assert!(x.iter().zip([1.0, 2.0, 3.0].iter()).all(|(a, b)| (a - b).abs() < 1e-9));
+        assert_close(&a.inverse().unwrap().checked_mul(&a).unwrap(), &DenseMatrix::identity(3));
+        assert_eq!(a.solve(&[1.0]), Err(MatrixError::DimensionMismatch { left: (3, 3), right: (1, 1) }));
+        assert_eq!(grid("1 2\n2 4").inverse(), Err(MatrixError::Singular));
+        assert_eq!(grid("1 2\n2 4").determinant(), Ok(0.0));
+    }
+
+    #[test]
+    fn ranks() {
+        assert_eq!(grid("1 2\n2 4").rank(), 1);
+        assert_eq!(grid("1 2 3 4\n2 4 6 8\n0 1 1 1").rank(), 2);
+        assert_eq!(grid("0 0\n0 0").rank(), 0);
+        assert_eq!(grid("1 0\n0 1\n1 1").rank(), 2);
+        assert_eq!(DenseMatrix::<i64>::from_grid("1 2 3 4\n2 4 6 8\n0 1 1 1").unwrap().bareiss_rank(), 2);
+    }
+
+    #[test]
+    fn nan_entries_give_nan_results() {
+        let nan_pivot = grid("NaN 1\n1 1");
+        let nan_elsewhere = grid("1 NaN\n2 3");
+
+        for m in [nan_pivot, nan_elsewhere].iter() {
+            assert!(m.determinant().expect("Matrix is square").is_nan());
+            assert!(m.solve(&[1.0, 2.0]).map_or(true, |x| x.iter().any(|n| n.is_nan())));
+            assert!(m.rank() <= 2);
+        }
+    }
+
+    #[test]
+    fn exact_integer_elimination() {
+        let int_grid = |g| DenseMatrix::<i64>::from_grid(g).expect("Failed to parse grid matrix");
+
+        assert_eq!(int_grid("2 1 1\n4 -6 0\n-2 7 2").bareiss_determinant(), Ok(-16));
+        assert_eq!(int_grid("0 1\n1 0").bareiss_determinant(), Ok(-1));
+        assert_eq!(int_grid("1 2\n2 4").bareiss_determinant(), Ok(0));
+        assert_eq!(int_grid("3 -1 4 1\n5 9 -2 6\n5 3 5 -8\n9 7 9 3").bareiss_determinant(), Ok(1620));
+
+        assert_eq!(int_grid("2 1\n1 3").solve_exact(&[1, 2]), Ok(vec![Ratio::new(1, 5), Ratio::new(3, 5)]));
+        assert_eq!(int_grid("1 2\n2 4").solve_exact(&[1, 2]), Err(MatrixError::Singular));
+    }
+}
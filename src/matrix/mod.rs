@@ -0,0 +1,119 @@
+
+pub mod lu;
+pub mod ops;
+pub mod parse;
+pub mod segments;
+pub mod sparse;
+pub mod view;
+
+use self::parse::{GridParseError, GridParser};
+use std::fmt;
+use std::str::FromStr;
+
+/// A matrix of any element type, stored row by row
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DenseMatrix<T = f64> {
+    ns: Vec<T>,
+    pub row_count: usize,
+    pub col_count: usize,
+}
+
+impl<T: FromStr> DenseMatrix<T> {
+
+    /// Constructs from a grid of fixed-size rows separated by newlines
+    /// and values in each row separated by spaces, skipping blank lines.
+    /// See `GridParser` for other layouts
+    pub fn from_grid(grid: &str) -> Result<DenseMatrix<T>, GridParseError> {
+        GridParser::new().parse(grid)
+    }
+}
+
+impl<T> DenseMatrix<T> {
+
+    /// The matrix with f applied to each entry, like converting integer entries to floats
+    pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> DenseMatrix<U> {
+        DenseMatrix { ns: self.ns.iter().map(f).collect(), row_count: self.row_count, col_count: self.col_count }
+    }
+}
+
+impl<T: Clone> DenseMatrix<T> {
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row < self.row_count && col < self.col_count {
+            self.ns.get(row*self.col_count+col).cloned()
+        } else {
+            None
+        }
+    }
+}
+
+/// Read access shared by dense and sparse matrices
+pub trait Matrix {
+    type Entry;
+
+    fn row_count(&self) -> usize;
+
+    fn col_count(&self) -> usize;
+
+    /// The entry at (row, column), or None outside the matrix
+    fn get(&self, row: usize, col: usize) -> Option<Self::Entry>;
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.row_count(), self.col_count())
+    }
+}
+
+impl<T: Clone> Matrix for DenseMatrix<T> {
+    type Entry = T;
+
+    fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<T> {
+        DenseMatrix::get(self, row, col)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for DenseMatrix<T> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for n in row {
+                match write!(f, "{} ", n) {
+                    Ok(_) => (),
+                    Err(err) => return Err(err)
+                }
+            }
+            match writeln!(f, "{}", "") {
+                Ok(_) => (),
+                Err(err) => return Err(err)
+            }
+        }
+        write!(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use num::BigUint;
+
+    #[test]
+    fn entries_of_any_parsable_type() {
+        let floats = DenseMatrix::<f64>::from_grid("0.5 -1.25\n1e3 2").expect("Failed to parse grid matrix");
+        let big = DenseMatrix::<BigUint>::from_grid("18446744073709551616 1").expect("Failed to parse grid matrix");
+
+        assert_eq!(floats.get(1, 0), Some(1000.0));
+        assert_eq!(floats.map(|&n| n * 2.0).get(0, 1), Some(-2.5));
+        assert_eq!(big.get(0, 0).map(|n| n.to_string()), Some("18446744073709551616".to_string()));
+        let bytes = DenseMatrix::<u8>::from_grid("1 2\n3 4").expect("Failed to parse grid matrix");
+        assert_eq!(bytes.map(|&n| n as u32 * 100).get(1, 1), Some(400));
+        assert_eq!(bytes.to_string(), "1 2 \n3 4 \n");
+    }
+}
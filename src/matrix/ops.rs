@@ -0,0 +1,274 @@
+use matrix::DenseMatrix;
+use num::{One, Zero};
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// Rows and columns of each block of the multiplication kernel
+const BLOCK_SIZE: usize = 64;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MatrixError {
+    /// The dimensions, as rows and columns, of the operands do not fit together
+    DimensionMismatch { left: (usize, usize), right: (usize, usize) },
+    /// The operation needs a square matrix but got one of these rows and columns
+    NotSquare(usize, usize),
+    /// The matrix has no inverse
+    Singular,
+    /// The entry at this row and column is outside the matrix
+    OutOfBounds { row: usize, col: usize },
+    /// A matrix of these rows and columns has more entries than fit in memory
+    TooLarge(usize, usize),
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MatrixError::DimensionMismatch { left, right } =>
+                write!(f, "dimensions {}x{} and {}x{} do not match", left.0, left.1, right.0, right.1),
+            MatrixError::NotSquare(rows, cols) => write!(f, "{}x{} matrix is not square", rows, cols),
+            MatrixError::Singular => write!(f, "matrix is singular"),
+            MatrixError::OutOfBounds { row, col } => write!(f, "({}, {}) is outside the matrix", row, col),
+            MatrixError::TooLarge(rows, cols) => write!(f, "{}x{} matrix is too large", rows, cols),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+impl<T> DenseMatrix<T> {
+
+    /// A matrix from its entries row by row, failing if they do not fill the given dimensions
+    pub fn from_vec(ns: Vec<T>, row_count: usize, col_count: usize) -> Result<DenseMatrix<T>, MatrixError> {
+        if row_count.checked_mul(col_count) != Some(ns.len()) {
+            return Err(MatrixError::DimensionMismatch { left: (row_count, col_count), right: (1, ns.len()) });
+        }
+        Ok(DenseMatrix { ns, row_count, col_count })
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.row_count == self.col_count
+    }
+}
+
+impl<T: Clone> DenseMatrix<T> {
+
+    /// A matrix with every entry set to n. Panics if it has too many entries; see `checked_filled`
+    pub fn filled(n: T, row_count: usize, col_count: usize) -> DenseMatrix<T> {
+        DenseMatrix::checked_filled(n, row_count, col_count).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// A matrix with every entry set to n, failing if the number of entries overflows
+    pub fn checked_filled(n: T, row_count: usize, col_count: usize) -> Result<DenseMatrix<T>, MatrixError> {
+        match row_count.checked_mul(col_count) {
+            Some(len) => Ok(DenseMatrix { ns: vec![n; len], row_count, col_count }),
+            None => Err(MatrixError::TooLarge(row_count, col_count)),
+        }
+    }
+
+    pub fn transpose(&self) -> DenseMatrix<T> {
+        let mut ns = Vec::with_capacity(self.ns.len());
+        for col in 0..self.col_count {
+            for row in 0..self.row_count {
+                ns.push(self.ns[row * self.col_count + col].clone());
+            }
+        }
+        DenseMatrix { ns, row_count: self.col_count, col_count: self.row_count }
+    }
+}
+
+impl<T: Clone + Zero> DenseMatrix<T> {
+
+    pub fn zeros(row_count: usize, col_count: usize) -> DenseMatrix<T> {
+        DenseMatrix::filled(T::zero(), row_count, col_count)
+    }
+
+    pub fn checked_zeros(row_count: usize, col_count: usize) -> Result<DenseMatrix<T>, MatrixError> {
+        DenseMatrix::checked_filled(T::zero(), row_count, col_count)
+    }
+}
+
+impl<T: Clone + Zero + One> DenseMatrix<T> {
+
+    /// The size by size matrix with ones on the diagonal and zeros elsewhere
+    pub fn identity(size: usize) -> DenseMatrix<T> {
+        let mut m = DenseMatrix::zeros(size, size);
+        for i in 0..size {
+            m.ns[i * size + i] = T::one();
+        }
+        m
+    }
+}
+
+impl<T: Clone + Add<Output = T>> DenseMatrix<T> {
+
+    /// Entry by entry sum, failing unless both matrices have the same dimensions
+    pub fn checked_add(&self, other: &DenseMatrix<T>) -> Result<DenseMatrix<T>, MatrixError> {
+        if self.dimensions() != other.dimensions() {
+            return Err(MatrixError::DimensionMismatch { left: self.dimensions(), right: other.dimensions() });
+        }
+        let ns = self.ns.iter().zip(other.ns.iter()).map(|(a, b)| a.clone() + b.clone()).collect();
+        Ok(DenseMatrix { ns, row_count: self.row_count, col_count: self.col_count })
+    }
+}
+
+impl<T: Clone + Mul<Output = T>> DenseMatrix<T> {
+
+    /// Every entry multiplied by k
+    pub fn scale(&self, k: &T) -> DenseMatrix<T> {
+        self.map(|n| n.clone() * k.clone())
+    }
+}
+
+impl<T: Clone + Zero + Mul<Output = T>> DenseMatrix<T> {
+
+    /// Matrix product, failing unless this matrix has as many columns as the other has rows.
+    /// Works through blocks of both so that they stay in cache for large matrices
+    pub fn checked_mul(&self, other: &DenseMatrix<T>) -> Result<DenseMatrix<T>, MatrixError> {
+        if self.col_count != other.row_count {
+            return Err(MatrixError::DimensionMismatch { left: self.dimensions(), right: other.dimensions() });
+        }
+        let (n, m, p) = (self.row_count, self.col_count, other.col_count);
+        let mut product = DenseMatrix::zeros(n, p);
+
+        for i0 in (0..n).step_by(BLOCK_SIZE) {
+            for k0 in (0..m).step_by(BLOCK_SIZE) {
+                for j0 in (0..p).step_by(BLOCK_SIZE) {
+                    for i in i0..(i0 + BLOCK_SIZE).min(n) {
+                        for k in k0..(k0 + BLOCK_SIZE).min(m) {
+                            let a = &self.ns[i * m + k];
+                            if a.is_zero() {
+                                continue;
+                            }
+                            for j in j0..(j0 + BLOCK_SIZE).min(p) {
+                                let entry = &mut product.ns[i * p + j];
+                                let sum = mem::replace(entry, T::zero());
+                                *entry = sum + a.clone() * other.ns[k * p + j].clone();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(product)
+    }
+}
+
+impl<T: Clone + Zero + One + Mul<Output = T>> DenseMatrix<T> {
+
+    /// The matrix multiplied by itself exp times by repeated squaring, failing unless it is square.
+    /// The 0th power is the identity
+    pub fn checked_pow(&self, exp: u64) -> Result<DenseMatrix<T>, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare(self.row_count, self.col_count));
+        }
+        let mut result = DenseMatrix::identity(self.row_count);
+        let mut base = self.clone();
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The entry at (row, column), panicking outside the matrix
+impl<T> Index<(usize, usize)> for DenseMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.row_count && col < self.col_count, "({}, {}) is outside the {}x{} matrix", row, col, self.row_count, self.col_count);
+        &self.ns[row * self.col_count + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DenseMatrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.row_count && col < self.col_count, "({}, {}) is outside the {}x{} matrix", row, col, self.row_count, self.col_count);
+        &mut self.ns[row * self.col_count + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use num::BigUint;
+
+    fn grid(grid: &str) -> DenseMatrix<i64> {
+        DenseMatrix::from_grid(grid).expect("Failed to parse grid matrix")
+    }
+
+    #[test]
+    fn entry_by_entry_operations() {
+        let a = grid("1 2 3\n4 5 6");
+
+        assert_eq!(a.checked_add(&a.scale(&10)), Ok(grid("11 22 33\n44 55 66")));
+        assert_eq!(a.checked_add(&a.transpose()), Err(MatrixError::DimensionMismatch { left: (2, 3), right: (3, 2) }));
+        assert_eq!(a.transpose(), grid("1 4\n2 5\n3 6"));
+        assert_eq!(DenseMatrix::from_vec(vec![1, 2, 3], 2, 2), Err(MatrixError::DimensionMismatch { left: (2, 2), right: (1, 3) }));
+        assert_eq!(DenseMatrix::from_vec(vec![1], usize::max_value(), 3).map(|m| m.dimensions()), Err(MatrixError::DimensionMismatch { left: (usize::max_value(), 3), right: (1, 1) }));
+        assert_eq!(DenseMatrix::<u8>::checked_zeros(usize::max_value(), 2), Err(MatrixError::TooLarge(usize::max_value(), 2)));
+        assert_eq!(DenseMatrix::checked_filled(7, 1, 2), Ok(grid("7 7")));
+
+        let mut b = a.clone();
+        b[(1, 2)] = -6;
+        assert_eq!(b[(1, 2)], -6);
+        assert_eq!(b[(0, 2)], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_outside_the_matrix() {
+        grid("1 2\n3 4")[(0, 2)];
+    }
+
+    #[test]
+    fn matrix_products() {
+        let a = grid("1 2 3\n4 5 6");
+        let b = grid("7 8\n9 10\n11 12");
+
+        assert_eq!(a.checked_mul(&b), Ok(grid("58 64\n139 154")));
+        assert_eq!(a.checked_mul(&a), Err(MatrixError::DimensionMismatch { left: (2, 3), right: (2, 3) }));
+        assert_eq!(DenseMatrix::identity(2).checked_mul(&a), Ok(a.clone()));
+        assert_eq!(a.checked_pow(2), Err(MatrixError::NotSquare(2, 3)));
+    }
+
+    #[test]
+    fn blocked_product_matches_naive_one() {
+        let size = BLOCK_SIZE * 2 + 5;
+        let ns: Vec<i64> = (0..size * (size + 3)).map(|i| (i as i64 * 7919) % 23 - 11).collect();
+        let a = DenseMatrix::from_vec(ns, size, size + 3).unwrap();
+        let b = a.transpose();
+        let product = a.checked_mul(&b).unwrap();
+
+        for &(i, j) in [(0, 0), (3, size - 1), (size - 1, 70), (BLOCK_SIZE, BLOCK_SIZE + 1)].iter() {
+            let expected: i64 = (0..size + 3).map(|k| a[(i, k)] * b[(k, j)]).sum();
+            assert_eq!(product[(i, j)], expected);
+        }
+    }
+
+    #[test]
+    fn fibonacci_by_matrix_powers() {
+        let step = DenseMatrix::from_vec(vec![1u64, 1, 1, 0], 2, 2).unwrap();
+
+        assert_eq!(step.checked_pow(0), Ok(DenseMatrix::identity(2)));
+        assert_eq!(step.checked_pow(10).map(|m| m[(0, 1)]), Ok(55));
+        assert_eq!(step.checked_pow(90).map(|m| m[(0, 1)]), Ok(2880067194370816120));
+
+        let big_step = step.map(|&n| BigUint::from(n));
+        assert_eq!(big_step.checked_pow(100).map(|m| m[(0, 1)].to_string()), Ok("354224848179261915075".to_string()));
+    }
+}
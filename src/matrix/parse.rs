@@ -0,0 +1,211 @@
+use matrix::DenseMatrix;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GridParseErrorKind {
+    /// The entry does not parse into the element type
+    BadEntry(String),
+    /// The row has a different number of entries than the rows before it
+    RaggedRow { expected: usize, found: usize },
+    /// Reading the grid failed
+    Io(io::ErrorKind),
+}
+
+/// Where and why a grid failed to parse, with lines and columns counted from 1.
+/// The column is the character where the bad entry starts, or 1 for a ragged row
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GridParseError {
+    pub kind: GridParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            GridParseErrorKind::BadEntry(ref entry) => write!(f, "invalid entry {:?}", entry),
+            GridParseErrorKind::RaggedRow { expected, found } => write!(f, "expected {} entries but found {}", expected, found),
+            GridParseErrorKind::Io(kind) => write!(f, "read failed: {:?}", kind),
+        }?;
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
+
+impl Error for GridParseError {}
+
+/// How entries are separated within a row
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Delimiter {
+    /// Any run of whitespace
+    Whitespace,
+    /// Exactly one of this character, with whitespace around entries ignored
+    Char(char),
+}
+
+/// Parses grids of rows separated by newlines into matrices. Blank lines are skipped
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GridParser {
+    delimiter: Delimiter,
+    comment_prefix: Option<String>,
+    header_lines: usize,
+}
+
+impl GridParser {
+
+    /// Entries separated by whitespace, with no comments or header
+    pub fn new() -> GridParser {
+        GridParser { delimiter: Delimiter::Whitespace, comment_prefix: None, header_lines: 0 }
+    }
+
+    pub fn delimiter(mut self, delimiter: Delimiter) -> GridParser {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Entries separated by commas
+    pub fn commas(self) -> GridParser {
+        self.delimiter(Delimiter::Char(','))
+    }
+
+    /// Entries separated by tabs
+    pub fn tabs(self) -> GridParser {
+        self.delimiter(Delimiter::Char('\t'))
+    }
+
+    /// Skips lines starting with the prefix, after any indentation
+    pub fn comment_prefix(mut self, prefix: &str) -> GridParser {
+        self.comment_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Skips the given number of lines at the start
+    pub fn skip_header_lines(mut self, lines: usize) -> GridParser {
+        self.header_lines = lines;
+        self
+    }
+
+    pub fn parse<T: FromStr>(&self, grid: &str) -> Result<DenseMatrix<T>, GridParseError> {
+        let mut builder = Builder::new();
+        for (ix, line) in grid.lines().enumerate() {
+            self.parse_line(&mut builder, ix + 1, line)?;
+        }
+        Ok(builder.build())
+    }
+
+    pub fn read<T: FromStr, R: BufRead>(&self, reader: R) -> Result<DenseMatrix<T>, GridParseError> {
+        let mut builder = Builder::new();
+        for (ix, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| GridParseError { kind: GridParseErrorKind::Io(e.kind()), line: ix + 1, column: 1 })?;
+            self.parse_line(&mut builder, ix + 1, &line)?;
+        }
+        Ok(builder.build())
+    }
+
+    fn parse_line<T: FromStr>(&self, builder: &mut Builder<T>, line_number: usize, line: &str) -> Result<(), GridParseError> {
+        let trimmed = line.trim();
+        let is_comment = self.comment_prefix.as_ref().map_or(false, |prefix| trimmed.starts_with(prefix.as_str()));
+        if line_number <= self.header_lines || trimmed.is_empty() || is_comment {
+            return Ok(());
+        }
+
+        let mut col_count = 0;
+        for (start, entry) in self.entries(line) {
+            let n = entry.parse().map_err(|_| GridParseError {
+                kind: GridParseErrorKind::BadEntry(entry.to_string()),
+                line: line_number,
+                column: line[..start].chars().count() + 1,
+            })?;
+            builder.ns.push(n);
+            col_count += 1;
+        }
+
+        match builder.col_count {
+            Some(expected) if expected != col_count => Err(GridParseError {
+                kind: GridParseErrorKind::RaggedRow { expected, found: col_count },
+                line: line_number,
+                column: 1,
+            }),
+            _ => {
+                builder.col_count = Some(col_count);
+                builder.row_count += 1;
+                Ok(())
+            },
+        }
+    }
+
+    /// Entries of the line with the byte offsets they start at
+    fn entries<'a>(&self, line: &'a str) -> Vec<(usize, &'a str)> {
+        let offset = |entry: &str| entry.as_ptr() as usize - line.as_ptr() as usize;
+        match self.delimiter {
+            Delimiter::Whitespace => line.split_whitespace().map(|e| (offset(e), e)).collect(),
+            Delimiter::Char(c) => line.split(c).map(|e| e.trim()).map(|e| (offset(e), e)).collect(),
+        }
+    }
+}
+
+impl Default for GridParser {
+    fn default() -> GridParser {
+        GridParser::new()
+    }
+}
+
+struct Builder<T> {
+    ns: Vec<T>,
+    row_count: usize,
+    col_count: Option<usize>,
+}
+
+impl<T> Builder<T> {
+
+    fn new() -> Builder<T> {
+        Builder { ns: Vec::new(), row_count: 0, col_count: None }
+    }
+
+    fn build(self) -> DenseMatrix<T> {
+        DenseMatrix { ns: self.ns, row_count: self.row_count, col_count: self.col_count.unwrap_or(0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn errors_point_at_the_entry_or_row() {
+        let bad_entry = DenseMatrix::<u32>::from_grid("1 2 3\n4 x5 6");
+        let ragged = DenseMatrix::<u32>::from_grid("1 2 3\n\n4 5 6\n7 8");
+
+        assert_eq!(bad_entry, Err(GridParseError { kind: GridParseErrorKind::BadEntry("x5".to_string()), line: 2, column: 3 }));
+        assert_eq!(ragged, Err(GridParseError { kind: GridParseErrorKind::RaggedRow { expected: 3, found: 2 }, line: 4, column: 1 }));
+        assert_eq!(ragged.unwrap_err().to_string(), "expected 3 entries but found 2 at line 4, column 1");
+        assert_eq!(DenseMatrix::<u32>::from_grid("\n1 2\n\n3 4\n\n").map(|m| m.dimensions()), Ok((2, 2)));
+        assert_eq!(DenseMatrix::<u32>::from_grid("").map(|m| m.dimensions()), Ok((0, 0)));
+    }
+
+    #[test]
+    fn delimiters_comments_and_headers() {
+        let csv = "a,b,c\n# totals follow\n1, 2,3\n  # indented comment\n4,5 , 6\n";
+        let parser = GridParser::new().commas().comment_prefix("#").skip_header_lines(1);
+
+        assert_eq!(parser.parse::<u8>(csv), DenseMatrix::from_grid("1 2 3\n4 5 6"));
+        assert_eq!(
+            GridParser::new().commas().parse::<u8>("1,,3").unwrap_err().kind,
+            GridParseErrorKind::BadEntry("".to_string())
+        );
+        assert_eq!(GridParser::new().tabs().parse::<String>("a b\tc\nd\te f").map(|m| m.get(1, 1)), Ok(Some("e f".to_string())));
+        assert_eq!(
+            GridParser::new().delimiter(Delimiter::Char(';')).parse::<f64>("1.5;é;3").unwrap_err(),
+            GridParseError { kind: GridParseErrorKind::BadEntry("é".to_string()), line: 1, column: 5 }
+        );
+    }
+
+    #[test]
+    fn reads_from_buffered_readers() {
+        let input = io::Cursor::new("1 2\n3 4\n");
+        assert_eq!(GridParser::new().read::<i32, _>(input), DenseMatrix::from_grid("1 2\n3 4"));
+    }
+}
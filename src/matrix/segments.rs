@@ -0,0 +1,426 @@
+use matrix::DenseMatrix;
+use num::{One, Zero};
+use std::ops::{Add, Div, Mul, Sub};
+
+/// The step from one entry of a segment to the next, in rows and columns
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Direction {
+    pub rows: isize,
+    pub cols: isize,
+}
+
+impl Direction {
+
+    pub const RIGHT: Direction = Direction { rows: 0, cols: 1 };
+    pub const LEFT: Direction = Direction { rows: 0, cols: -1 };
+    pub const DOWN: Direction = Direction { rows: 1, cols: 0 };
+    pub const UP: Direction = Direction { rows: -1, cols: 0 };
+    pub const DOWN_RIGHT: Direction = Direction { rows: 1, cols: 1 };
+    pub const DOWN_LEFT: Direction = Direction { rows: 1, cols: -1 };
+    pub const UP_RIGHT: Direction = Direction { rows: -1, cols: 1 };
+    pub const UP_LEFT: Direction = Direction { rows: -1, cols: -1 };
+
+    pub fn new(rows: isize, cols: isize) -> Direction {
+        Direction { rows, cols }
+    }
+
+    /// The opposite step
+    pub fn reversed(&self) -> Direction {
+        Direction { rows: -self.rows, cols: -self.cols }
+    }
+
+    /// Right, down and both diagonals down, which between them cover every line of adjacent entries once
+    pub fn lines() -> [Direction; 4] {
+        [Direction::RIGHT, Direction::DOWN, Direction::DOWN_RIGHT, Direction::DOWN_LEFT]
+    }
+
+    /// All eight steps to a neighbouring entry
+    pub fn neighbours() -> [Direction; 8] {
+        [
+            Direction::RIGHT, Direction::DOWN_RIGHT, Direction::DOWN, Direction::DOWN_LEFT,
+            Direction::LEFT, Direction::UP_LEFT, Direction::UP, Direction::UP_RIGHT,
+        ]
+    }
+}
+
+/// Entries of a matrix a fixed step apart, borrowed rather than copied
+#[derive(Debug)]
+pub struct Segment<'a, T: 'a> {
+    matrix: &'a DenseMatrix<T>,
+    start: (usize, usize),
+    direction: Direction,
+    length: usize,
+    wrap: bool,
+}
+
+impl<'a, T> Clone for Segment<'a, T> {
+    fn clone(&self) -> Segment<'a, T> {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Segment<'a, T> {}
+
+impl<'a, T> Segment<'a, T> {
+
+    /// Row and column of the first entry
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Row and column of the ith entry
+    pub fn position(&self, i: usize) -> Option<(usize, usize)> {
+        if i >= self.length {
+            return None;
+        }
+        let row = self.start.0 as isize + i as isize * self.direction.rows;
+        let col = self.start.1 as isize + i as isize * self.direction.cols;
+        if self.wrap {
+            Some((row.rem_euclid(self.matrix.row_count as isize) as usize, col.rem_euclid(self.matrix.col_count as isize) as usize))
+        } else {
+            Some((row as usize, col as usize))
+        }
+    }
+
+    pub fn get(&self, i: usize) -> Option<&'a T> {
+        self.position(i).map(|(row, col)| &self.matrix.ns[row * self.matrix.col_count + col])
+    }
+
+    pub fn iter(&self) -> SegmentIter<'a, T> {
+        SegmentIter { segment: *self, next: 0 }
+    }
+}
+
+impl<'a, T: Clone> Segment<'a, T> {
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<'a, T> IntoIterator for Segment<'a, T> {
+    type Item = &'a T;
+    type IntoIter = SegmentIter<'a, T>;
+
+    fn into_iter(self) -> SegmentIter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct SegmentIter<'a, T: 'a> {
+    segment: Segment<'a, T>,
+    next: usize,
+}
+
+impl<'a, T> Iterator for SegmentIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let entry = self.segment.get(self.next)?;
+        self.next += 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.segment.length - self.next.min(self.segment.length);
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SegmentIter<'a, T> {}
+
+/// Every segment of a length and direction, by the row and then the column it starts at
+pub struct Segments<'a, T: 'a> {
+    matrix: &'a DenseMatrix<T>,
+    direction: Direction,
+    length: usize,
+    wrap: bool,
+    /// Row by row index of the next start to try
+    next_start: usize,
+}
+
+impl<'a, T> Segments<'a, T> {
+
+    fn fits(&self, (row, col): (usize, usize)) -> bool {
+        if self.wrap {
+            return true;
+        }
+        let last = (self.length - 1) as isize;
+        let end_row = row as isize + last * self.direction.rows;
+        let end_col = col as isize + last * self.direction.cols;
+        end_row >= 0 && end_row < self.matrix.row_count as isize && end_col >= 0 && end_col < self.matrix.col_count as isize
+    }
+}
+
+impl<'a, T> Iterator for Segments<'a, T> {
+    type Item = Segment<'a, T>;
+
+    fn next(&mut self) -> Option<Segment<'a, T>> {
+        if self.length == 0 {
+            return None;
+        }
+        while self.next_start < self.matrix.ns.len() {
+            let start = (self.next_start / self.matrix.col_count, self.next_start % self.matrix.col_count);
+            self.next_start += 1;
+            if self.fits(start) {
+                return Some(Segment { matrix: self.matrix, start, direction: self.direction, length: self.length, wrap: self.wrap });
+            }
+        }
+        None
+    }
+}
+
+impl<T> DenseMatrix<T> {
+
+    /// All the segments of the given length and direction that fit in the matrix
+    pub fn segments<'a>(&'a self, length: usize, direction: Direction) -> Segments<'a, T> {
+        Segments { matrix: self, direction, length, wrap: false, next_start: 0 }
+    }
+
+    /// Segments of the given length and direction starting at every entry,
+    /// wrapping around the edges as if the matrix were a torus
+    pub fn wrapping_segments<'a>(&'a self, length: usize, direction: Direction) -> Segments<'a, T> {
+        Segments { matrix: self, direction, length, wrap: true, next_start: 0 }
+    }
+}
+
+/// The segment with the largest value found by a search, and where it is
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BestSegment<T> {
+    pub value: T,
+    /// Row and column of the first entry
+    pub start: (usize, usize),
+    pub direction: Direction,
+}
+
+impl<T: Clone> DenseMatrix<T> {
+
+    /// Largest sum of the given number of adjacent entries in a line in any direction, or None if no segment fits.
+    /// Slides a window along each line, so each entry is added and subtracted once per direction
+    pub fn max_segment_sum(&self, length: usize) -> Option<BestSegment<T>>
+        where T: Zero + Add<Output = T> + Sub<Output = T> + PartialOrd {
+
+        self.max_rolling(length, |window: &mut (T,), entering, leaving| {
+            // Leaving first, so the sum never covers more than a window and overflows only if a window does
+            if let Some(n) = leaving {
+                window.0 = window.0.clone() - n.clone();
+            }
+            window.0 = window.0.clone() + entering.clone();
+            window.0.clone()
+        })
+    }
+
+    /// Largest product of the given number of adjacent entries in a line in any direction, or None if no segment fits.
+    /// Slides a window along each line, keeping the product of its nonzero entries and a count of its zeros
+    /// so that entries leaving the window can be divided out. Floating point products may drift slightly
+    pub fn max_segment_product(&self, length: usize) -> Option<BestSegment<T>>
+        where T: Zero + One + Mul<Output = T> + Div<Output = T> + PartialOrd {
+
+        self.max_rolling(length, |window: &mut (T, usize), entering, leaving| {
+            // Leaving first, so the product never covers more than a window and overflows only if a window does
+            match leaving {
+                Some(n) if n.is_zero() => window.1 -= 1,
+                Some(n) => window.0 = window.0.clone() / n.clone(),
+                None => {},
+            }
+            if entering.is_zero() {
+                window.1 += 1;
+            } else {
+                window.0 = window.0.clone() * entering.clone();
+            }
+            if window.1 > 0 { T::zero() } else { window.0.clone() }
+        })
+    }
+
+    /// Slides windows of the given length along every line in the four line directions, with step
+    /// updating the window state by the entry entering it and the one leaving it, and giving its value
+    fn max_rolling<S, F>(&self, length: usize, mut step: F) -> Option<BestSegment<T>>
+        where T: PartialOrd, S: Initial<T>, F: FnMut(&mut S, &T, Option<&T>) -> T {
+
+        let mut best: Option<BestSegment<T>> = None;
+        if length == 0 {
+            return best;
+        }
+
+        for &direction in Direction::lines().iter() {
+            for start in self.line_starts(direction) {
+                let mut window = S::initial();
+                let mut positions = Vec::with_capacity(length);
+                let (mut row, mut col) = (start.0 as isize, start.1 as isize);
+                let mut i = 0;
+
+                while row >= 0 && row < self.row_count as isize && col >= 0 && col < self.col_count as isize {
+                    let entering = &self.ns[row as usize * self.col_count + col as usize];
+                    // Positions of the window, overwritten round-robin
+                    let slot = i % length;
+                    let leaving = if i >= length {
+                        let (r, c) = positions[slot];
+                        Some(&self.ns[r * self.col_count + c])
+                    } else {
+                        None
+                    };
+                    let value = step(&mut window, entering, leaving);
+                    if i >= length {
+                        positions[slot] = (row as usize, col as usize);
+                    } else {
+                        positions.push((row as usize, col as usize));
+                    }
+
+                    if i + 1 >= length && best.as_ref().map_or(true, |b| value > b.value) {
+                        best = Some(BestSegment { value, start: positions[(i + 1) % length], direction });
+                    }
+                    row += direction.rows;
+                    col += direction.cols;
+                    i += 1;
+                }
+            }
+        }
+        best
+    }
+
+    /// Entries with no entry before them in the direction
+    fn line_starts(&self, direction: Direction) -> Vec<(usize, usize)> {
+        let inside = |row: isize, col: isize| row >= 0 && row < self.row_count as isize && col >= 0 && col < self.col_count as isize;
+        (0..self.row_count)
+            .flat_map(|row| (0..self.col_count).map(move |col| (row, col)))
+            .filter(|&(row, col)| !inside(row as isize - direction.rows, col as isize - direction.cols))
+            .collect()
+    }
+}
+
+/// State of a rolling window before it has any entries
+trait Initial<T> {
+    fn initial() -> Self;
+}
+
+/// A running sum
+impl<T: Zero> Initial<T> for (T,) {
+    fn initial() -> (T,) {
+        (T::zero(),)
+    }
+}
+
+/// The product of the nonzero entries and the number of zeros
+impl<T: One> Initial<T> for (T, usize) {
+    fn initial() -> (T, usize) {
+        (T::one(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn grid() -> DenseMatrix<u32> {
+        DenseMatrix::from_grid("1  2  3  4
+                                7  8  9  10
+                                25 26 27 28").expect("Failed to parse grid matrix")
+    }
+
+    fn segments(matrix: &DenseMatrix<u32>, length: usize, direction: Direction) -> Vec<Vec<u32>> {
+        matrix.segments(length, direction).map(|s| s.to_vec()).collect()
+    }
+
+    #[test]
+    fn correct_horizontal_segments() {
+        assert_eq!(segments(&grid(), 2, Direction::RIGHT), vec![
+            vec![1, 2], vec![2, 3], vec![3, 4], vec![7, 8], vec![8, 9], vec![9, 10], vec![25, 26], vec![26, 27], vec![27, 28]
+        ]);
+    }
+
+    #[test]
+    fn correct_vertical_segments() {
+        assert_eq!(segments(&grid(), 2, Direction::DOWN), vec![
+            vec![1, 7], vec![2, 8], vec![3, 9], vec![4, 10], vec![7, 25], vec![8, 26], vec![9, 27], vec![10, 28]
+        ]);
+    }
+
+    #[test]
+    fn correct_diagonal_segments() {
+        assert_eq!(segments(&grid(), 2, Direction::DOWN_RIGHT), vec![
+            vec![1, 8], vec![2, 9], vec![3, 10], vec![7, 26], vec![8, 27], vec![9, 28]
+        ]);
+        assert_eq!(segments(&grid(), 2, Direction::DOWN_LEFT), vec![
+            vec![2, 7], vec![3, 8], vec![4, 9], vec![8, 25], vec![9, 26], vec![10, 27]
+        ]);
+    }
+
+    #[test]
+    fn reversed_and_knight_segments() {
+        let matrix = grid();
+
+        assert_eq!(segments(&matrix, 3, Direction::LEFT.reversed()), segments(&matrix, 3, Direction::RIGHT));
+        assert_eq!(segments(&matrix, 3, Direction::UP_LEFT), vec![vec![27, 8, 1], vec![28, 9, 2]]);
+        assert_eq!(segments(&matrix, 2, Direction::new(1, 2)), vec![
+            vec![1, 9], vec![2, 10], vec![7, 27], vec![8, 28]
+        ]);
+        assert_eq!(matrix.segments(2, Direction::new(1, 2)).map(|s| s.start()).collect::<Vec<_>>(), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(matrix.segments(4, Direction::DOWN).count(), 0);
+        assert_eq!(matrix.segments(0, Direction::DOWN).count(), 0);
+    }
+
+    #[test]
+    fn wrapping_segments() {
+        let matrix = grid();
+        let wrapped: Vec<Segment<u32>> = matrix.wrapping_segments(3, Direction::DOWN_RIGHT).collect();
+
+        assert_eq!(wrapped.len(), 12);
+        assert_eq!(wrapped[3].start(), (0, 3));
+        assert_eq!(wrapped[3].to_vec(), vec![4, 7, 26]);
+        assert_eq!(wrapped[3].position(2), Some((2, 1)));
+        assert_eq!(wrapped[3].iter().len(), 3);
+        assert_eq!(matrix.wrapping_segments(5, Direction::UP).next().map(|s| s.to_vec()), Some(vec![1, 25, 7, 1, 25]));
+    }
+
+    #[test]
+    fn rolling_products_match_segment_products() {
+        let matrix = DenseMatrix::<u64>::from_grid("3 0 5 2 7
+                                                    1 9 0 0 4
+                                                    6 2 8 3 0
+                                                    0 5 1 9 2").expect("Failed to parse grid matrix");
+
+        for length in 1..6 {
+            let brute = Direction::lines().iter()
+                .flat_map(|&d| matrix.segments(length, d))
+                .map(|s| s.iter().product::<u64>())
+                .max();
+            assert_eq!(matrix.max_segment_product(length).map(|b| b.value), brute);
+        }
+        assert_eq!(
+            matrix.max_segment_product(2),
+            Some(BestSegment { value: 72, start: (1, 1), direction: Direction::DOWN_RIGHT })
+        );
+        assert_eq!(matrix.max_segment_product(0), None);
+
+        let large = DenseMatrix::<u64>::from_grid("2097152 2097152 2097152 2097152").expect("Failed to parse grid matrix");
+        assert_eq!(large.max_segment_product(3).map(|b| b.value), Some(1 << 63));
+    }
+
+    #[test]
+    fn rolling_sums_with_negative_entries() {
+        let matrix = DenseMatrix::<i32>::from_grid("-1 -2 -3
+                                                    -4  5 -6
+                                                    -7 -8  9").expect("Failed to parse grid matrix");
+
+        assert_eq!(matrix.max_segment_sum(3), Some(BestSegment { value: 13, start: (0, 0), direction: Direction::DOWN_RIGHT }));
+        assert_eq!(matrix.max_segment_sum(1).map(|b| (b.value, b.start)), Some((9, (2, 2))));
+        assert_eq!(matrix.max_segment_sum(2).map(|b| b.value), Some(14));
+        assert_eq!(matrix.max_segment_sum(4), None);
+
+        let large = DenseMatrix::<u8>::from_grid("100 100 100 100").expect("Failed to parse grid matrix");
+        assert_eq!(large.max_segment_sum(2).map(|b| b.value), Some(200));
+    }
+}
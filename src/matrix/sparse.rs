@@ -0,0 +1,498 @@
+use matrix::{DenseMatrix, Matrix};
+use matrix::ops::MatrixError;
+use matrix::parse::{GridParseError, GridParseErrorKind};
+use num::Zero;
+use std::mem;
+use std::ops::Mul;
+use std::str::FromStr;
+
+/// Rows and columns an unbounded `from_triplets` accepts, so that input alone cannot make a matrix
+/// too large to hold. Use `from_triplets_within` for larger matrices
+pub const MAX_TRIPLET_DIMENSION: usize = 1 << 20;
+
+/// A sparse matrix as a list of (row, column, value) triplets in any order, which is the easiest form to build.
+/// Repeated positions add up. Convert to a `SparseMatrix` for lookups and arithmetic
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CooMatrix<T> {
+    row_count: usize,
+    col_count: usize,
+    entries: Vec<(usize, usize, T)>,
+}
+
+impl<T> CooMatrix<T> {
+
+    /// A matrix of the given dimensions with no entries
+    pub fn new(row_count: usize, col_count: usize) -> CooMatrix<T> {
+        CooMatrix { row_count, col_count, entries: Vec::new() }
+    }
+
+    /// Adds n at (row, column), failing outside the matrix
+    pub fn push(&mut self, row: usize, col: usize, n: T) -> Result<(), MatrixError> {
+        if row >= self.row_count || col >= self.col_count {
+            return Err(MatrixError::OutOfBounds { row, col });
+        }
+        self.entries.push((row, col, n));
+        Ok(())
+    }
+
+    /// The same entries in a matrix of new dimensions, failing if any entry falls outside it
+    pub fn with_dimensions(mut self, row_count: usize, col_count: usize) -> Result<CooMatrix<T>, MatrixError> {
+        if let Some(&(row, col, _)) = self.entries.iter().find(|&&(row, col, _)| row >= row_count || col >= col_count) {
+            return Err(MatrixError::OutOfBounds { row, col });
+        }
+        self.row_count = row_count;
+        self.col_count = col_count;
+        Ok(self)
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+
+    /// The triplets in the order they were added
+    pub fn entries(&self) -> &[(usize, usize, T)] {
+        &self.entries
+    }
+
+    pub fn transpose(self) -> CooMatrix<T> {
+        let entries = self.entries.into_iter().map(|(row, col, n)| (col, row, n)).collect();
+        CooMatrix { row_count: self.col_count, col_count: self.row_count, entries }
+    }
+}
+
+impl<T: Clone + Zero> CooMatrix<T> {
+
+    /// The nonzero entries of a dense matrix
+    pub fn from_dense(matrix: &DenseMatrix<T>) -> CooMatrix<T> {
+        let entries = matrix.iter_indexed()
+            .filter(|&(_, n)| !n.is_zero())
+            .map(|((row, col), n)| (row, col, n.clone()))
+            .collect();
+        CooMatrix { row_count: matrix.row_count, col_count: matrix.col_count, entries }
+    }
+
+    /// The entries in a dense matrix, failing if it would have too many entries
+    pub fn to_dense(&self) -> Result<DenseMatrix<T>, MatrixError> {
+        let mut dense = DenseMatrix::checked_zeros(self.row_count, self.col_count)?;
+        for &(row, col, ref n) in &self.entries {
+            let entry = &mut dense[(row, col)];
+            let sum = mem::replace(entry, T::zero());
+            *entry = sum + n.clone();
+        }
+        Ok(dense)
+    }
+
+    /// Sorts the triplets into compressed rows, adding up repeated positions and dropping zeros
+    pub fn to_csr(&self) -> SparseMatrix<T> {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|&(row, col, _)| (row, col));
+
+        let mut merged: Vec<(usize, usize, T)> = Vec::with_capacity(entries.len());
+        for (row, col, n) in entries {
+            match merged.last_mut() {
+                Some(last) if last.0 == row && last.1 == col => {
+                    let sum = mem::replace(&mut last.2, T::zero());
+                    last.2 = sum + n;
+                },
+                _ => merged.push((row, col, n)),
+            }
+        }
+
+        let mut row_starts = vec![0; self.row_count + 1];
+        let mut col_indices = Vec::with_capacity(merged.len());
+        let mut values = Vec::with_capacity(merged.len());
+        for (row, col, n) in merged.into_iter().filter(|entry| !entry.2.is_zero()) {
+            row_starts[row + 1] += 1;
+            col_indices.push(col);
+            values.push(n);
+        }
+        for row in 0..self.row_count {
+            row_starts[row + 1] += row_starts[row];
+        }
+        SparseMatrix { row_count: self.row_count, col_count: self.col_count, row_starts, col_indices, values }
+    }
+}
+
+impl<T: FromStr> CooMatrix<T> {
+
+    /// Parses lines of `row column value` separated by whitespace, with rows and columns counted from 0.
+    /// Blank lines and lines starting with `%` or `#` are skipped. The matrix is just big enough for the
+    /// entries, and rows and columns from `MAX_TRIPLET_DIMENSION` on are rejected as bad entries
+    pub fn from_triplets(text: &str) -> Result<CooMatrix<T>, GridParseError> {
+        CooMatrix::parse_triplets(text, None)
+    }
+
+    /// Same as `from_triplets` but for a matrix of the given dimensions, failing on entries outside it
+    pub fn from_triplets_within(text: &str, row_count: usize, col_count: usize) -> Result<CooMatrix<T>, GridParseError> {
+        CooMatrix::parse_triplets(text, Some((row_count, col_count)))
+    }
+
+    fn parse_triplets(text: &str, dimensions: Option<(usize, usize)>) -> Result<CooMatrix<T>, GridParseError> {
+        let (mut matrix, bounded) = match dimensions {
+            Some((row_count, col_count)) => (CooMatrix::new(row_count, col_count), true),
+            None => (CooMatrix::new(0, 0), false),
+        };
+        for (ix, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('%') || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 3 {
+                let kind = GridParseErrorKind::RaggedRow { expected: 3, found: parts.len() };
+                return Err(GridParseError { kind, line: ix + 1, column: 1 });
+            }
+            let bad_entry = |part: &str| GridParseError {
+                kind: GridParseErrorKind::BadEntry(part.to_string()),
+                line: ix + 1,
+                column: line[..part.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1,
+            };
+            // An index is out of range if it is outside the given dimensions, or past the limit without them
+            let index = |part: &str, count: usize| {
+                part.parse::<usize>().ok()
+                    .filter(|&i| i < if bounded { count } else { MAX_TRIPLET_DIMENSION })
+                    .ok_or_else(|| bad_entry(part))
+            };
+            let row = index(parts[0], matrix.row_count)?;
+            let col = index(parts[1], matrix.col_count)?;
+            let n = parts[2].parse().map_err(|_| bad_entry(parts[2]))?;
+
+            if !bounded {
+                matrix.row_count = matrix.row_count.max(row + 1);
+                matrix.col_count = matrix.col_count.max(col + 1);
+            }
+            matrix.entries.push((row, col, n));
+        }
+        Ok(matrix)
+    }
+}
+
+impl<T: Clone + Zero> Matrix for CooMatrix<T> {
+    type Entry = T;
+
+    fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    /// The sum of the triplets at (row, column), looking through all of them
+    fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row >= self.row_count || col >= self.col_count {
+            return None;
+        }
+        Some(self.entries.iter()
+            .filter(|&&(r, c, _)| r == row && c == col)
+            .fold(T::zero(), |sum, (_, _, n)| sum + n.clone()))
+    }
+}
+
+/// A sparse matrix in compressed sparse row (CSR) form: the column and value of each nonzero entry,
+/// row by row and sorted by column within each row, so memory grows with the entries rather than the dimensions
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseMatrix<T> {
+    row_count: usize,
+    col_count: usize,
+    /// Index into the columns and values where each row starts, with one more for the end of the last row
+    row_starts: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> SparseMatrix<T> {
+
+    /// A matrix of the given dimensions with every entry zero
+    pub fn new(row_count: usize, col_count: usize) -> SparseMatrix<T> {
+        SparseMatrix { row_count, col_count, row_starts: vec![0; row_count + 1], col_indices: Vec::new(), values: Vec::new() }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.row_count, self.col_count)
+    }
+
+    /// Number of entries stored, which are all nonzero
+    pub fn entry_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The stored entries of a row with their columns, left to right
+    pub fn row<'a>(&'a self, row: usize) -> Option<SparseRow<'a, T>> {
+        if row < self.row_count {
+            let range = self.row_starts[row]..self.row_starts[row + 1];
+            Some(SparseRow { cols: &self.col_indices[range.clone()], values: &self.values[range], next: 0 })
+        } else {
+            None
+        }
+    }
+
+    /// The stored entries with their row and column, row by row
+    pub fn iter_indexed<'a>(&'a self) -> SparseEntries<'a, T> {
+        SparseEntries { matrix: self, row: 0, next: 0 }
+    }
+}
+
+impl<T: Clone + Zero> SparseMatrix<T> {
+
+    pub fn from_dense(matrix: &DenseMatrix<T>) -> SparseMatrix<T> {
+        CooMatrix::from_dense(matrix).to_csr()
+    }
+
+    /// The entries in a dense matrix, failing if it would have too many entries
+    pub fn to_dense(&self) -> Result<DenseMatrix<T>, MatrixError> {
+        let mut dense = DenseMatrix::checked_zeros(self.row_count, self.col_count)?;
+        for ((row, col), n) in self.iter_indexed() {
+            dense[(row, col)] = n.clone();
+        }
+        Ok(dense)
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        let entries = self.iter_indexed().map(|((row, col), n)| (row, col, n.clone())).collect();
+        CooMatrix { row_count: self.row_count, col_count: self.col_count, entries }
+    }
+
+    /// Counts the entries in each column to place them directly into the rows of the transpose
+    pub fn transpose(&self) -> SparseMatrix<T> {
+        let mut row_starts = vec![0; self.col_count + 1];
+        for &col in &self.col_indices {
+            row_starts[col + 1] += 1;
+        }
+        for col in 0..self.col_count {
+            row_starts[col + 1] += row_starts[col];
+        }
+
+        let mut next = row_starts.clone();
+        let mut col_indices = vec![0; self.values.len()];
+        let mut sources = vec![0; self.values.len()];
+        for row in 0..self.row_count {
+            for i in self.row_starts[row]..self.row_starts[row + 1] {
+                let target = next[self.col_indices[i]];
+                next[self.col_indices[i]] += 1;
+                col_indices[target] = row;
+                sources[target] = i;
+            }
+        }
+        let values = sources.into_iter().map(|i| self.values[i].clone()).collect();
+        SparseMatrix { row_count: self.col_count, col_count: self.row_count, row_starts, col_indices, values }
+    }
+}
+
+impl<T: Clone + Zero + Mul<Output = T>> SparseMatrix<T> {
+
+    /// Product with a dense matrix, failing unless this matrix has as many columns as the other has rows.
+    /// Only the stored entries are multiplied
+    pub fn checked_mul(&self, other: &DenseMatrix<T>) -> Result<DenseMatrix<T>, MatrixError> {
+        if self.col_count != other.row_count {
+            return Err(MatrixError::DimensionMismatch { left: self.dimensions(), right: other.dimensions() });
+        }
+        let p = other.col_count;
+        let mut product = DenseMatrix::checked_zeros(self.row_count, p)?;
+
+        for row in 0..self.row_count {
+            for i in self.row_starts[row]..self.row_starts[row + 1] {
+                let (k, a) = (self.col_indices[i], &self.values[i]);
+                for j in 0..p {
+                    let entry = &mut product.ns[row * p + j];
+                    let sum = mem::replace(entry, T::zero());
+                    *entry = sum + a.clone() * other.ns[k * p + j].clone();
+                }
+            }
+        }
+        Ok(product)
+    }
+}
+
+impl<T: Clone + Zero> Matrix for SparseMatrix<T> {
+    type Entry = T;
+
+    fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    /// Binary searches the row, giving zero for entries that are not stored
+    fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row >= self.row_count || col >= self.col_count {
+            return None;
+        }
+        let range = self.row_starts[row]..self.row_starts[row + 1];
+        match self.col_indices[range.clone()].binary_search(&col) {
+            Ok(i) => Some(self.values[range.start + i].clone()),
+            Err(_) => Some(T::zero()),
+        }
+    }
+}
+
+/// Stored entries of one row of a sparse matrix with their columns
+pub struct SparseRow<'a, T: 'a> {
+    cols: &'a [usize],
+    values: &'a [T],
+    next: usize,
+}
+
+impl<'a, T> Iterator for SparseRow<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        let entry = (*self.cols.get(self.next)?, &self.values[self.next]);
+        self.next += 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.cols.len() - self.next;
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SparseRow<'a, T> {}
+
+/// Stored entries of a sparse matrix with their row and column, row by row
+pub struct SparseEntries<'a, T: 'a> {
+    matrix: &'a SparseMatrix<T>,
+    row: usize,
+    next: usize,
+}
+
+impl<'a, T> Iterator for SparseEntries<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<((usize, usize), &'a T)> {
+        if self.next >= self.matrix.values.len() {
+            return None;
+        }
+        // Skip past rows that end at or before the next entry, including empty ones
+        while self.matrix.row_starts[self.row + 1] <= self.next {
+            self.row += 1;
+        }
+        let entry = ((self.row, self.matrix.col_indices[self.next]), &self.matrix.values[self.next]);
+        self.next += 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.matrix.values.len() - self.next;
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SparseEntries<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn dense(grid: &str) -> DenseMatrix<i64> {
+        DenseMatrix::from_grid(grid).expect("Failed to parse grid matrix")
+    }
+
+    fn trace<M: Matrix<Entry = i64>>(m: &M) -> i64 {
+        (0..m.row_count().min(m.col_count())).filter_map(|i| m.get(i, i)).sum()
+    }
+
+    #[test]
+    fn conversions_keep_the_entries() {
+        let matrix = dense("0 2 0 0\n0 0 0 0\n5 0 0 7");
+        let sparse = SparseMatrix::from_dense(&matrix);
+
+        assert_eq!(sparse.entry_count(), 3);
+        assert_eq!(sparse.to_dense(), Ok(matrix));
+        assert_eq!(sparse.to_coo().entries(), &[(0, 1, 2), (2, 0, 5), (2, 3, 7)][..]);
+        assert_eq!(sparse.to_coo().to_csr(), sparse);
+        assert_eq!(sparse.row(2).map(|r| r.collect::<Vec<_>>()), Some(vec![(0, &5), (3, &7)]));
+        assert_eq!(sparse.row(1).map(|r| r.len()), Some(0));
+        assert!(sparse.row(3).is_none());
+        assert_eq!(SparseMatrix::<i64>::new(2, 3).to_dense(), Ok(DenseMatrix::zeros(2, 3)));
+    }
+
+    #[test]
+    fn triplets_add_up_and_drop_zeros() {
+        let mut coo = CooMatrix::new(2, 2);
+        coo.push(1, 1, 4).unwrap();
+        coo.push(0, 1, 3).unwrap();
+        coo.push(1, 1, -1).unwrap();
+        coo.push(1, 0, 6).unwrap();
+        coo.push(1, 0, -6).unwrap();
+
+        assert_eq!(coo.push(2, 0, 1), Err(MatrixError::OutOfBounds { row: 2, col: 0 }));
+        assert_eq!(coo.to_dense(), Ok(dense("0 3\n0 3")));
+        assert_eq!(coo.to_csr().entry_count(), 2);
+        assert_eq!(coo.get(1, 1), Some(3));
+        assert_eq!(coo.clone().transpose().to_dense(), Ok(dense("0 0\n3 3")));
+        assert_eq!(coo.clone().with_dimensions(1, 2), Err(MatrixError::OutOfBounds { row: 1, col: 1 }));
+        assert_eq!(coo.with_dimensions(3, 2).and_then(|m| m.to_dense()), Ok(dense("0 3\n0 3\n0 0")));
+    }
+
+    #[test]
+    fn transpose_and_multiply_like_dense() {
+        let matrix = dense("1 0 0 2\n0 0 3 0\n0 4 0 0\n5 0 6 0\n0 0 0 0");
+        let sparse = SparseMatrix::from_dense(&matrix);
+        let other = dense("1 2\n3 4\n5 6\n7 8");
+
+        assert_eq!(sparse.transpose().to_dense(), Ok(matrix.transpose()));
+        assert_eq!(sparse.transpose().transpose(), sparse);
+        assert_eq!(sparse.checked_mul(&other), matrix.checked_mul(&other));
+        assert_eq!(sparse.checked_mul(&matrix), Err(MatrixError::DimensionMismatch { left: (5, 4), right: (5, 4) }));
+    }
+
+    #[test]
+    fn parses_triplet_lists() {
+        let text = "% adjacency list\n0 1 1.5\n\n  2 0 -2\n# self loop\n1 1 3\n";
+        let coo = CooMatrix::<f64>::from_triplets(text).expect("Failed to parse triplets");
+
+        assert_eq!(coo.dimensions(), (3, 2));
+        assert_eq!(coo.to_dense(), Ok(DenseMatrix::from_grid("0 1.5\n0 3\n-2 0").unwrap()));
+        assert_eq!(
+            CooMatrix::<f64>::from_triplets("0 0 1\n1 x 2"),
+            Err(GridParseError { kind: GridParseErrorKind::BadEntry("x".to_string()), line: 2, column: 3 })
+        );
+        assert_eq!(
+            CooMatrix::<f64>::from_triplets("0 0").unwrap_err().kind,
+            GridParseErrorKind::RaggedRow { expected: 3, found: 2 }
+        );
+        assert_eq!(CooMatrix::<u8>::from_triplets("").map(|m| m.dimensions()), Ok((0, 0)));
+
+        let too_big = format!("0 {} 1", usize::max_value());
+        assert_eq!(
+            CooMatrix::<u8>::from_triplets(&too_big).unwrap_err(),
+            GridParseError { kind: GridParseErrorKind::BadEntry(usize::max_value().to_string()), line: 1, column: 3 }
+        );
+        let limit = format!("{} 0 1", MAX_TRIPLET_DIMENSION);
+        assert_eq!(
+            CooMatrix::<u8>::from_triplets(&limit).unwrap_err(),
+            GridParseError { kind: GridParseErrorKind::BadEntry(MAX_TRIPLET_DIMENSION.to_string()), line: 1, column: 1 }
+        );
+        let largest = format!("{} 0 1", MAX_TRIPLET_DIMENSION - 1);
+        assert_eq!(CooMatrix::<u8>::from_triplets(&largest).map(|m| m.to_csr().entry_count()), Ok(1));
+
+        let huge = CooMatrix::<u8>::new(usize::max_value(), 2);
+        assert_eq!(huge.to_dense(), Err(MatrixError::TooLarge(usize::max_value(), 2)));
+        let within = CooMatrix::<u8>::from_triplets_within("0 1 5\n1 0 6", 4, 2).expect("Failed to parse triplets");
+        assert_eq!(within.dimensions(), (4, 2));
+        assert_eq!(within.to_dense(), Ok(DenseMatrix::from_grid("0 5\n6 0\n0 0\n0 0").unwrap()));
+        assert_eq!(
+            CooMatrix::<u8>::from_triplets_within("0 1 5\n1000000 0 6", 4, 2).unwrap_err(),
+            GridParseError { kind: GridParseErrorKind::BadEntry("1000000".to_string()), line: 2, column: 1 }
+        );
+    }
+
+    #[test]
+    fn shared_matrix_trait() {
+        let matrix = dense("1 0 9\n0 2 0\n8 0 3");
+        let sparse = SparseMatrix::from_dense(&matrix);
+        let coo = sparse.to_coo();
+
+        assert_eq!((trace(&matrix), trace(&sparse), trace(&coo)), (6, 6, 6));
+        assert_eq!(Matrix::get(&sparse, 1, 0), Some(0));
+        assert_eq!(Matrix::get(&sparse, 0, 2), Some(9));
+        assert_eq!(Matrix::get(&sparse, 3, 0), None);
+        assert_eq!(Matrix::dimensions(&coo), (3, 3));
+    }
+}
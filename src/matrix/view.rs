@@ -0,0 +1,442 @@
+use matrix::DenseMatrix;
+use std::iter::{Enumerate, StepBy};
+use std::ops::{Index, IndexMut, Range};
+use std::slice;
+
+/// Rows of a matrix or view, top to bottom
+pub struct Rows<'a, T: 'a> {
+    ns: &'a [T],
+    /// Entries from the start of one row to the start of the next
+    stride: usize,
+    cols: Range<usize>,
+    rows: Range<usize>,
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        let row = self.rows.next()?;
+        Some(&self.ns[row * self.stride + self.cols.start..row * self.stride + self.cols.end])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Rows<'a, T> {}
+
+/// A column of a matrix, top to bottom
+#[derive(Debug)]
+pub struct Col<'a, T: 'a> {
+    ns: &'a [T],
+    /// Entries from one row to the next
+    stride: usize,
+    col: usize,
+    len: usize,
+}
+
+impl<'a, T> Clone for Col<'a, T> {
+    fn clone(&self) -> Col<'a, T> {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Col<'a, T> {}
+
+impl<'a, T> Col<'a, T> {
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The entry in the given row
+    pub fn get(&self, row: usize) -> Option<&'a T> {
+        if row < self.len {
+            Some(&self.ns[row * self.stride + self.col])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> StepBy<slice::Iter<'a, T>> {
+        // Without rows there are no entries, and the column may be past the end
+        let from = self.col.min(self.ns.len());
+        self.ns[from..].iter().step_by(self.stride)
+    }
+}
+
+impl<'a, T: Clone> Col<'a, T> {
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<'a, T> IntoIterator for Col<'a, T> {
+    type Item = &'a T;
+    type IntoIter = StepBy<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> StepBy<slice::Iter<'a, T>> {
+        self.iter()
+    }
+}
+
+/// Columns of a matrix, left to right
+pub struct Cols<'a, T: 'a> {
+    ns: &'a [T],
+    stride: usize,
+    len: usize,
+    cols: Range<usize>,
+}
+
+impl<'a, T> Iterator for Cols<'a, T> {
+    type Item = Col<'a, T>;
+
+    fn next(&mut self) -> Option<Col<'a, T>> {
+        let col = self.cols.next()?;
+        Some(Col { ns: self.ns, stride: self.stride, col, len: self.len })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cols.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Cols<'a, T> {}
+
+/// Entries of a matrix or view with their row and column, row by row
+pub struct IndexedEntries<'a, T: 'a> {
+    rows: Rows<'a, T>,
+    row: usize,
+    current: Enumerate<slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for IndexedEntries<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<((usize, usize), &'a T)> {
+        loop {
+            if let Some((col, n)) = self.current.next() {
+                return Some(((self.row - 1, col), n));
+            }
+            self.current = self.rows.next()?.iter().enumerate();
+            self.row += 1;
+        }
+    }
+}
+
+fn indexed<'a, T>(rows: Rows<'a, T>) -> IndexedEntries<'a, T> {
+    IndexedEntries { rows, row: 0, current: [].iter().enumerate() }
+}
+
+/// Whether the ranges are in order and within the dimensions
+fn fits(rows: &Range<usize>, cols: &Range<usize>, (row_count, col_count): (usize, usize)) -> bool {
+    rows.start <= rows.end && rows.end <= row_count && cols.start <= cols.end && cols.end <= col_count
+}
+
+impl<T> DenseMatrix<T> {
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.row_count {
+            Some(&self.ns[row * self.col_count..(row + 1) * self.col_count])
+        } else {
+            None
+        }
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        if row < self.row_count {
+            Some(&mut self.ns[row * self.col_count..(row + 1) * self.col_count])
+        } else {
+            None
+        }
+    }
+
+    /// The entries of a column, top to bottom
+    pub fn col<'a>(&'a self, col: usize) -> Option<Col<'a, T>> {
+        if col < self.col_count {
+            Some(Col { ns: &self.ns, stride: self.col_count, col, len: self.row_count })
+        } else {
+            None
+        }
+    }
+
+    pub fn rows<'a>(&'a self) -> Rows<'a, T> {
+        Rows { ns: &self.ns, stride: self.col_count, cols: 0..self.col_count, rows: 0..self.row_count }
+    }
+
+    pub fn cols<'a>(&'a self) -> Cols<'a, T> {
+        Cols { ns: &self.ns, stride: self.col_count, len: self.row_count, cols: 0..self.col_count }
+    }
+
+    pub fn iter_indexed<'a>(&'a self) -> IndexedEntries<'a, T> {
+        indexed(self.rows())
+    }
+
+    /// The entries in the given rows and columns, borrowed, or None if they reach outside the matrix
+    pub fn view<'a>(&'a self, rows: Range<usize>, cols: Range<usize>) -> Option<MatrixView<'a, T>> {
+        if fits(&rows, &cols, self.dimensions()) {
+            Some(MatrixView { matrix: self, rows, cols })
+        } else {
+            None
+        }
+    }
+
+    /// The entries in the given rows and columns, mutably borrowed, or None if they reach outside the matrix
+    pub fn view_mut<'a>(&'a mut self, rows: Range<usize>, cols: Range<usize>) -> Option<MatrixViewMut<'a, T>> {
+        if fits(&rows, &cols, self.dimensions()) {
+            Some(MatrixViewMut { matrix: self, rows, cols })
+        } else {
+            None
+        }
+    }
+}
+
+/// A rectangle of a matrix, with rows and columns counted from its top left corner
+#[derive(Debug)]
+pub struct MatrixView<'a, T: 'a> {
+    matrix: &'a DenseMatrix<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+}
+
+impl<'a, T> Clone for MatrixView<'a, T> {
+    fn clone(&self) -> MatrixView<'a, T> {
+        MatrixView { matrix: self.matrix, rows: self.rows.clone(), cols: self.cols.clone() }
+    }
+}
+
+impl<'a, T> MatrixView<'a, T> {
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows.len(), self.cols.len())
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        let (row_count, col_count) = self.dimensions();
+        if row < row_count && col < col_count {
+            Some(&self.matrix.ns[(self.rows.start + row) * self.matrix.col_count + self.cols.start + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> Option<&'a [T]> {
+        self.rows().nth(row)
+    }
+
+    pub fn rows(&self) -> Rows<'a, T> {
+        Rows { ns: &self.matrix.ns, stride: self.matrix.col_count, cols: self.cols.clone(), rows: self.rows.clone() }
+    }
+
+    pub fn iter_indexed(&self) -> IndexedEntries<'a, T> {
+        indexed(self.rows())
+    }
+
+    /// A rectangle of this view, with ranges relative to it
+    pub fn view(&self, rows: Range<usize>, cols: Range<usize>) -> Option<MatrixView<'a, T>> {
+        if fits(&rows, &cols, self.dimensions()) {
+            let rows = self.rows.start + rows.start..self.rows.start + rows.end;
+            let cols = self.cols.start + cols.start..self.cols.start + cols.end;
+            Some(MatrixView { matrix: self.matrix, rows, cols })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Clone> MatrixView<'a, T> {
+
+    /// A new matrix with a copy of the entries
+    pub fn to_matrix(&self) -> DenseMatrix<T> {
+        let (row_count, col_count) = self.dimensions();
+        let ns = self.rows().flat_map(|row| row.iter().cloned()).collect();
+        DenseMatrix { ns, row_count, col_count }
+    }
+}
+
+/// The entry at (row, column) of the view, panicking outside it
+impl<'a, T> Index<(usize, usize)> for MatrixView<'a, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let (row_count, col_count) = self.dimensions();
+        self.get(row, col).unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} view", row, col, row_count, col_count))
+    }
+}
+
+/// A rectangle of a matrix that can be changed in place, with rows and columns counted from its top left corner
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T: 'a> {
+    matrix: &'a mut DenseMatrix<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows.len(), self.cols.len())
+    }
+
+    /// Borrows the view immutably, for reading
+    pub fn as_view<'b>(&'b self) -> MatrixView<'b, T> {
+        MatrixView { matrix: self.matrix, rows: self.rows.clone(), cols: self.cols.clone() }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.index_of(row, col).map(|i| &self.matrix.ns[i])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.index_of(row, col).map(move |i| &mut self.matrix.ns[i])
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        if row < self.rows.len() {
+            let start = (self.rows.start + row) * self.matrix.col_count;
+            Some(&mut self.matrix.ns[start + self.cols.start..start + self.cols.end])
+        } else {
+            None
+        }
+    }
+
+    /// Applies f to every entry of the view
+    pub fn apply<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        for row in 0..self.rows.len() {
+            if let Some(entries) = self.row_mut(row) {
+                entries.iter_mut().for_each(&mut f);
+            }
+        }
+    }
+
+    /// Index into the matrix's entries of (row, column) of the view
+    fn index_of(&self, row: usize, col: usize) -> Option<usize> {
+        if row < self.rows.len() && col < self.cols.len() {
+            Some((self.rows.start + row) * self.matrix.col_count + self.cols.start + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Clone> MatrixViewMut<'a, T> {
+
+    /// Sets every entry of the view to n
+    pub fn fill(&mut self, n: T) {
+        self.apply(|entry| *entry = n.clone());
+    }
+
+    /// Overwrites the view with the entries of a matrix of the same dimensions, or returns false if they differ
+    pub fn copy_from(&mut self, other: &DenseMatrix<T>) -> bool {
+        if self.dimensions() != other.dimensions() {
+            return false;
+        }
+        for (row, entries) in other.rows().enumerate() {
+            if let Some(target) = self.row_mut(row) {
+                target.clone_from_slice(entries);
+            }
+        }
+        true
+    }
+}
+
+/// The entry at (row, column) of the view, panicking outside it
+impl<'a, T> Index<(usize, usize)> for MatrixViewMut<'a, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let (row_count, col_count) = self.dimensions();
+        self.get(row, col).unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} view", row, col, row_count, col_count))
+    }
+}
+
+impl<'a, T> IndexMut<(usize, usize)> for MatrixViewMut<'a, T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (row_count, col_count) = self.dimensions();
+        self.get_mut(row, col).unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} view", row, col, row_count, col_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn grid() -> DenseMatrix<u32> {
+        DenseMatrix::from_grid("1  2  3  4
+                                5  6  7  8
+                                9  10 11 12").expect("Failed to parse grid matrix")
+    }
+
+    #[test]
+    fn rows_columns_and_indexed_entries() {
+        let matrix = grid();
+
+        assert_eq!(matrix.row(1), Some(&[5, 6, 7, 8][..]));
+        assert_eq!(matrix.row(3), None);
+        assert_eq!(matrix.col(2).map(|c| c.to_vec()), Some(vec![3, 7, 11]));
+        assert!(matrix.col(4).is_none());
+        assert_eq!(matrix.rows().len(), 3);
+        assert_eq!(matrix.cols().map(|c| c.iter().sum::<u32>()).collect::<Vec<_>>(), vec![15, 18, 21, 24]);
+
+        let indexed: Vec<((usize, usize), &u32)> = matrix.iter_indexed().collect();
+        assert_eq!(indexed.len(), 12);
+        assert_eq!(indexed[6], ((1, 2), &7));
+        assert!(matrix.iter_indexed().all(|((row, col), &n)| matrix[(row, col)] == n));
+        assert_eq!(DenseMatrix::<u32>::from_grid("").unwrap().iter_indexed().count(), 0);
+
+        let no_rows = DenseMatrix::<u32>::from_vec(vec![], 0, 3).unwrap();
+        assert_eq!(no_rows.cols().len(), 3);
+        assert!(no_rows.cols().all(|c| c.is_empty() && c.iter().next().is_none()));
+        assert_eq!(no_rows.col(2).map(|c| c.len()), Some(0));
+        assert_eq!(matrix.col(3).and_then(|c| c.get(2)), Some(&12));
+    }
+
+    #[test]
+    fn views_borrow_rectangles() {
+        let matrix = grid();
+        let view = matrix.view(1..3, 1..4).expect("View is inside the matrix");
+
+        assert_eq!(view.dimensions(), (2, 3));
+        assert_eq!(view[(0, 0)], 6);
+        assert_eq!(view.get(1, 3), None);
+        assert_eq!(view.row(1), Some(&[10, 11, 12][..]));
+        assert_eq!(view.iter_indexed().nth(4), Some(((1, 1), &11)));
+        assert_eq!(view.to_matrix(), DenseMatrix::from_grid("6 7 8\n10 11 12").unwrap());
+        assert_eq!(view.view(1..2, 0..2).map(|v| v.to_matrix()), DenseMatrix::from_grid("10 11").ok());
+        assert!(view.view(0..3, 0..1).is_none());
+        assert!(matrix.view(0..1, 2..5).is_none());
+        assert_eq!(matrix.view(2..2, 0..4).map(|v| v.rows().count()), Some(0));
+    }
+
+    #[test]
+    fn mutable_views_change_the_matrix() {
+        let mut matrix = grid();
+        {
+            let mut view = matrix.view_mut(0..2, 2..4).expect("View is inside the matrix");
+            view[(1, 1)] = 80;
+            view.row_mut(0).unwrap()[0] = 30;
+            assert_eq!(view.as_view().to_matrix(), DenseMatrix::from_grid("30 4\n7 80").unwrap());
+            view.apply(|n| *n += 1);
+            assert!(!view.copy_from(&DenseMatrix::zeros(1, 2)));
+        }
+        assert_eq!(matrix, DenseMatrix::from_grid("1 2 31 5\n5 6 8 81\n9 10 11 12").unwrap());
+
+        matrix.view_mut(1..3, 0..2).unwrap().fill(0);
+        matrix.row_mut(0).unwrap()[0] = 100;
+        assert_eq!(matrix, DenseMatrix::from_grid("100 2 31 5\n0 0 8 81\n0 0 11 12").unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_outside_the_view() {
+        grid().view(0..2, 0..2).unwrap()[(0, 2)];
+    }
+}
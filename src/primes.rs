@@ -1,30 +1,126 @@
 
+use ints::gcd;
 use std::collections::HashMap;
 
+/// Cofactors left after trial division by the cached primes up to this are split by Pollard's rho
+const SMALL_PRIME_LIMIT: u64 = 1000;
+
+/// Prime factors of n in ascending order, trial dividing by the small cached primes first
 pub fn factorize(n: u64, primes: &mut CachedPrimes) -> Vec<u64> {
 
     let mut factors = Vec::new();
 
     let mut buff = n;
-    while buff > 1 {
-        let factor = primes.iter().find(|p| buff % *p == 0).unwrap(); // Should never be None
-        factors.push(factor);
-        buff = buff / factor;
-    };
+    if buff > 1 {
+        for &p in primes.up_to(SMALL_PRIME_LIMIT) {
+            if p * p > buff {
+                break;
+            }
+            while buff % p == 0 {
+                factors.push(p);
+                buff /= p;
+            }
+        }
+    }
 
+    factorize_large(buff, &mut factors);
+    factors.sort();
+    factors
+}
+
+/// Prime factors of n in ascending order, without a prime cache
+pub fn prime_factors(n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    factorize_large(n, &mut factors);
+    factors.sort();
     factors
 }
 
 /// Unique prime factors and their occurrence counts
 pub fn factorize_unique(n: u64, primes: &mut CachedPrimes) -> HashMap<u64, u32> {
+    count_occurrences(factorize(n, primes))
+}
+
+/// Unique prime factors and their occurrence counts, without a prime cache
+pub fn prime_factors_unique(n: u64) -> HashMap<u64, u32> {
+    count_occurrences(prime_factors(n))
+}
+
+fn count_occurrences(factors: Vec<u64>) -> HashMap<u64, u32> {
     let mut result: HashMap<u64, u32> = HashMap::new();
-    for factor in factorize(n, primes) {
-        let new_count = result.get(&factor).unwrap_or(&0) + 1;
-        result.insert(factor, new_count);
+    for factor in factors {
+        *result.entry(factor).or_insert(0) += 1;
     }
     result
 }
 
+/// Pushes the prime factors of n by recursively splitting it with Pollard's rho
+fn factorize_large(n: u64, factors: &mut Vec<u64>) {
+    if n <= 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let divisor = pollard_brent(n);
+    factorize_large(divisor, factors);
+    factorize_large(n / divisor, factors);
+}
+
+/// A non-trivial divisor of the composite n by Brent's variant of Pollard's rho
+fn pollard_brent(n: u64) -> u64 {
+    // Number of steps whose differences are multiplied together before taking a gcd
+    const BATCH_SIZE: u64 = 128;
+
+    if n % 2 == 0 {
+        return 2;
+    }
+
+    for c in 1..n {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let distance = |a: u64, b: u64| if a > b { a - b } else { b - a };
+
+        let (mut x, mut y, mut ys) = (2, 2, 2);
+        let (mut g, mut r, mut q) = (1, 1, 1);
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH_SIZE.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, distance(x, y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH_SIZE;
+            }
+            r *= 2;
+        }
+
+        // The batch overshot, so step back through it one gcd at a time
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd(distance(x, ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+
+        if g != n {
+            return g;
+        }
+    }
+
+    panic!("Failed to find a divisor of {}, is it prime?", n)
+}
+
 pub struct PrimesIterator {n: u64}
 
 impl PrimesIterator {
@@ -46,23 +142,121 @@ fn next_after(n: u64) -> u64 {
     (n+1..).find(|&pc| is_prime(pc)).expect("Failed to find the next prime for some reason")
 }
 
-pub struct CachedPrimes { values: Vec<u64> }
+/// How `CachedPrimes` finds more primes once its cache is exhausted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Extension {
+    /// One prime at a time by trial division of the candidates following the last cached prime
+    TrialDivision,
+    /// A segment of the given size at a time by a segmented Sieve of Eratosthenes
+    Sieve(usize),
+}
+
+/// Default sieve segment size, small enough for the segment to stay in the L1 cache
+pub const DEFAULT_SEGMENT_SIZE: usize = 1 << 15;
+
+/// The largest limit `CachedPrimes` sieves up to. It leaves room above for a sieving prime, all of
+/// which are below 2^32, to be added to any multiple without overflowing
+pub const MAX_CACHED_LIMIT: u64 = u64::max_value() - (1 << 32);
+
+pub struct CachedPrimes {
+    values: Vec<u64>,
+    /// All the primes below this bound are cached
+    bound: u64,
+    extension: Extension,
+}
 
 impl CachedPrimes {
     pub fn with_capacity(c: usize) -> CachedPrimes {
-        CachedPrimes { values: Vec::with_capacity(c) }
+        let mut primes = CachedPrimes::new();
+        primes.values.reserve(c);
+        primes
     }
 
     pub fn new() -> CachedPrimes {
-        CachedPrimes { values: Vec::new() }
+        CachedPrimes::with_extension(Extension::Sieve(DEFAULT_SEGMENT_SIZE))
+    }
+
+    pub fn with_extension(extension: Extension) -> CachedPrimes {
+        if let Extension::Sieve(segment_size) = extension {
+            assert!(segment_size >= 2, "Sieve segment size must be at least 2");
+        }
+        CachedPrimes { values: Vec::new(), bound: 2, extension }
     }
 
     pub fn iter(&mut self) -> CachedPrimesIterator {
         CachedPrimesIterator { cached: self, next_index_to_serve: 0 }
     }
+
+    /// All the primes less than or equal to `limit`, in ascending order. Limits above
+    /// `MAX_CACHED_LIMIT` are lowered to it
+    pub fn up_to(&mut self, limit: u64) -> &[u64] {
+        let limit = limit.min(MAX_CACHED_LIMIT);
+        while self.bound <= limit {
+            self.extend();
+        }
+        let count = match self.values.binary_search(&limit) {
+            Ok(ix) => ix + 1,
+            Err(ix) => ix,
+        };
+        &self.values[..count]
+    }
+
+    /// Caches at least one more prime
+    fn extend(&mut self) {
+        assert!(self.bound <= MAX_CACHED_LIMIT, "No primes are cached above {}", MAX_CACHED_LIMIT);
+        match self.extension {
+            Extension::TrialDivision => {
+                let next = next_after(self.bound - 1);
+                self.values.push(next);
+                self.bound = next + 1;
+            },
+            Extension::Sieve(segment_size) => {
+                let previous_count = self.values.len();
+                while self.values.len() == previous_count {
+                    self.sieve_segment(segment_size as u64);
+                }
+            },
+        }
+    }
+
+    /// Sieves the primes in [bound, bound + size) into the cache, using the cached
+    /// primes as sieving primes. The segment is shortened when needed so that its end
+    /// does not exceed the square of the bound, as that's all the cache can sieve, nor pass
+    /// `MAX_CACHED_LIMIT`.
+    fn sieve_segment(&mut self, size: u64) {
+        let low = self.bound;
+        let high = low.saturating_add(size).min(low.saturating_mul(low)).min(MAX_CACHED_LIMIT + 1);
+
+        let mut composite = vec![false; (high - low) as usize];
+        for &p in self.values.iter().take_while(|&&p| p.saturating_mul(p) < high) {
+            let mut multiple = (p * p).max((low + p - 1) / p * p);
+            while multiple < high {
+                composite[(multiple - low) as usize] = true;
+                multiple += p;
+            }
+        }
+
+        for (offset, &is_composite) in composite.iter().enumerate() {
+            if !is_composite {
+                self.values.push(low + offset as u64);
+            }
+        }
+        self.bound = high;
+    }
+}
+
+/// All the primes less than `limit` by a segmented Sieve of Eratosthenes
+pub fn primes_below(limit: u64) -> Vec<u64> {
+    if limit < 3 {
+        return Vec::new();
+    }
+    let mut primes = CachedPrimes::new();
+    let count = primes.up_to(limit - 1).len();
+    primes.values.truncate(count);
+    primes.values
 }
 
-struct CachedPrimesIterator<'a> {
+pub struct CachedPrimesIterator<'a> {
     cached: &'a mut CachedPrimes,
     next_index_to_serve: usize,
 }
@@ -71,21 +265,81 @@ impl<'a> Iterator for CachedPrimesIterator<'a> {
     type Item = u64;
     fn next(&mut self) -> Option<u64> {
         if self.next_index_to_serve == self.cached.values.len() {
-            let next = next_after(*self.cached.values.last().unwrap_or(&1));
-            self.cached.values.push(next);
-            self.next_index_to_serve += 1;
-            Some(next)
-        } else {
-            let next = *self.cached.values.get(self.next_index_to_serve).expect("Failed to find prime in cache");
-            self.next_index_to_serve += 1;
-            Some(next)
+            self.cached.extend();
         }
+        let next = *self.cached.values.get(self.next_index_to_serve).expect("Failed to find prime in cache");
+        self.next_index_to_serve += 1;
+        Some(next)
     }
 }
 
+/// Values below this are tested by trial division, which beats Miller-Rabin for them
+const TRIAL_DIVISION_LIMIT: u64 = 1 << 16;
+
+/// Bases for which Miller-Rabin is deterministic for every 64-bit integer
+const MILLER_RABIN_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
 pub fn is_prime(n: u64) -> bool {
-    let sqrt_n: u64 = (n as f64).sqrt() as u64;
-    !(2..(sqrt_n+1)).any(|divisor| n % divisor == 0)
+    if n < TRIAL_DIVISION_LIMIT {
+        is_prime_by_trial_division(n)
+    } else {
+        is_prime_by_miller_rabin(n)
+    }
+}
+
+/// Primality by trial division by every candidate up to the square root of n
+pub fn is_prime_by_trial_division(n: u64) -> bool {
+    n >= 2 && !(2..).take_while(|&divisor| divisor <= n / divisor).any(|divisor| n % divisor == 0)
+}
+
+/// Deterministic Miller-Rabin primality test over the whole u64 range
+pub fn is_prime_by_miller_rabin(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in MILLER_RABIN_WITNESSES.iter() {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    MILLER_RABIN_WITNESSES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// (a * b) mod m without overflowing
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// (base ^ exp) mod m by repeated squaring
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
 }
 
 #[cfg(test)]
@@ -104,4 +358,101 @@ mod tests {
         assert_eq!(cached_1, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
         assert_eq!(cached_1, cached_2);
     }
+
+    #[test]
+    fn sieving_stops_at_the_maximum_limit() {
+        // Too few sieving primes to be correct this high, but enough to exercise the arithmetic at the top
+        let mut primes = CachedPrimes::with_extension(Extension::Sieve(64));
+        primes.values = primes_below(1000);
+        primes.bound = MAX_CACHED_LIMIT - 100;
+
+        assert_eq!(primes.up_to(u64::max_value()).last().map(|&p| p <= MAX_CACHED_LIMIT), Some(true));
+        assert_eq!(primes.bound, MAX_CACHED_LIMIT + 1);
+    }
+
+    #[test]
+    fn sieved_primes_match_trial_division() {
+        let mut trial = CachedPrimes::with_extension(Extension::TrialDivision);
+        let expected: Vec<u64> = trial.iter().take(2000).collect();
+
+        for &segment_size in &[2, 7, 64, DEFAULT_SEGMENT_SIZE] {
+            let mut sieved = CachedPrimes::with_extension(Extension::Sieve(segment_size));
+            let actual: Vec<u64> = sieved.iter().take(2000).collect();
+            assert_eq!(actual, expected, "Segment size {}", segment_size);
+        }
+    }
+
+    #[test]
+    fn primes_up_to_and_below_limit() {
+        let mut cached_primes = CachedPrimes::new();
+
+        assert_eq!(cached_primes.up_to(1), &[] as &[u64]);
+        assert_eq!(cached_primes.up_to(2), &[2]);
+        assert_eq!(cached_primes.up_to(29), &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_below(29), vec![2, 3, 5, 7, 11, 13, 17, 19, 23]);
+        assert_eq!(primes_below(2), vec![]);
+        assert_eq!(primes_below(1000000).len(), 78498);
+    }
+
+    #[test]
+    fn small_primality_agrees_across_tests() {
+        for n in 0..20000 {
+            assert_eq!(is_prime_by_miller_rabin(n), is_prime_by_trial_division(n), "n = {}", n);
+        }
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(is_prime(2));
+    }
+
+    #[test]
+    fn large_primality_is_correct() {
+        // Carmichael number and a strong pseudoprime to the bases 2, 3, 5 and 7
+        assert!(!is_prime(561));
+        assert!(!is_prime(3215031751));
+
+        assert!(is_prime(1000000007));
+        assert!(is_prime(4294967291));
+        assert!(!is_prime(4294967297)); // 641 * 6700417
+        assert!(is_prime(18446744073709551557)); // Largest 64-bit prime
+        assert!(!is_prime(18446744073709551615));
+        assert!(!is_prime(4294967291 * 4294967279));
+    }
+
+    #[test]
+    fn factorize_matches_trial_division() {
+        let mut ps = CachedPrimes::new();
+        for n in 2..5000 {
+            let mut expected = Vec::new();
+            let mut m = n;
+            let mut d = 2;
+            while m > 1 {
+                while m % d == 0 {
+                    expected.push(d);
+                    m /= d;
+                }
+                d += 1;
+            }
+            assert_eq!(factorize(n, &mut ps), expected, "n = {}", n);
+            assert_eq!(prime_factors(n), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn factorize_large_numbers() {
+        let mut ps = CachedPrimes::new();
+
+        assert_eq!(factorize(0, &mut ps), vec![]);
+        assert_eq!(factorize(1, &mut ps), vec![]);
+        assert_eq!(factorize(600851475143, &mut ps), vec![71, 839, 1471, 6857]);
+        assert_eq!(factorize(4294967291 * 4294967279, &mut ps), vec![4294967279, 4294967291]);
+        assert_eq!(factorize(18446744073709551557, &mut ps), vec![18446744073709551557]);
+        assert_eq!(prime_factors(18446744073709551615), vec![3, 5, 17, 257, 641, 65537, 6700417]);
+        assert_eq!(prime_factors(1 << 63), vec![2; 63]);
+
+        let unique = prime_factors_unique(1024 * 243 * 1000000007);
+        assert_eq!(unique.len(), 3);
+        assert_eq!(unique[&2], 10);
+        assert_eq!(unique[&3], 5);
+        assert_eq!(unique[&1000000007], 1);
+    }
 }
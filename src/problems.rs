@@ -0,0 +1,288 @@
+use days;
+use fibs;
+use ints;
+use matrix::DenseMatrix;
+use num::BigUint;
+use numbers;
+use palindromes;
+use primes;
+use sequences;
+use subseq;
+use triangles;
+
+/// A Project Euler problem along with the solver implemented in this crate
+pub struct Problem {
+    pub number: u32,
+    pub title: &'static str,
+    /// The input given in the problem statement
+    pub input: &'static str,
+    /// The known answer for the default input
+    pub answer: u64,
+    pub solver: fn(&str) -> u64,
+}
+
+impl Problem {
+
+    /// Solves the problem for its default input
+    pub fn solve(&self) -> u64 {
+        (self.solver)(self.input)
+    }
+
+    /// Solves the problem for a custom input in the same format as the default one
+    pub fn solve_with(&self, input: &str) -> u64 {
+        (self.solver)(input)
+    }
+}
+
+/// All the solved problems ordered by their number
+pub static PROBLEMS: &'static [Problem] = &[
+    Problem {
+        number: 2,
+        title: "Even Fibonacci numbers",
+        input: "4000000",
+        answer: 4613732,
+        solver: solve_2,
+    },
+    Problem {
+        number: 3,
+        title: "Largest prime factor",
+        input: "600851475143",
+        answer: 6857,
+        solver: solve_3,
+    },
+    Problem {
+        number: 4,
+        title: "Largest palindrome product",
+        input: "3",
+        answer: 906609,
+        solver: solve_4,
+    },
+    Problem {
+        number: 5,
+        title: "Smallest multiple",
+        input: "20",
+        answer: 232792560,
+        solver: solve_5,
+    },
+    Problem {
+        number: 7,
+        title: "10001st prime",
+        input: "10001",
+        answer: 104743,
+        solver: solve_7,
+    },
+    Problem {
+        number: 8,
+        title: "Largest product in a series",
+        input: include_str!("../data/p008_series.txt"),
+        answer: 23514624000,
+        solver: solve_8,
+    },
+    Problem {
+        number: 9,
+        title: "Special Pythagorean triplet",
+        input: "1000",
+        answer: 31875000,
+        solver: solve_9,
+    },
+    Problem {
+        number: 10,
+        title: "Summation of primes",
+        input: "2000000",
+        answer: 142913828922,
+        solver: solve_10,
+    },
+    Problem {
+        number: 11,
+        title: "Largest product in a grid",
+        input: include_str!("../data/p011_grid.txt"),
+        answer: 70600674,
+        solver: solve_11,
+    },
+    Problem {
+        number: 12,
+        title: "Highly divisible triangular number",
+        input: "500",
+        answer: 76576500,
+        solver: solve_12,
+    },
+    Problem {
+        number: 14,
+        title: "Longest Collatz sequence",
+        input: "1000000",
+        answer: 837799,
+        solver: solve_14,
+    },
+    Problem {
+        number: 15,
+        title: "Lattice paths",
+        input: "20",
+        answer: 137846528820,
+        solver: solve_15,
+    },
+    Problem {
+        number: 17,
+        title: "Number letter counts",
+        input: "1000",
+        answer: 21124,
+        solver: solve_17,
+    },
+    Problem {
+        number: 18,
+        title: "Maximum path sum I",
+        input: include_str!("../data/p018_triangle.txt"),
+        answer: 1074,
+        solver: solve_18,
+    },
+    Problem {
+        number: 19,
+        title: "Counting Sundays",
+        input: "1901 2000",
+        answer: 171,
+        solver: solve_19,
+    },
+    Problem {
+        number: 20,
+        title: "Factorial digit sum",
+        input: "100",
+        answer: 648,
+        solver: solve_20,
+    },
+    Problem {
+        number: 25,
+        title: "1000-digit Fibonacci number",
+        input: "1000",
+        answer: 4782,
+        solver: solve_25,
+    },
+];
+
+/// Looks up a solved problem by its number
+pub fn find(number: u32) -> Option<&'static Problem> {
+    PROBLEMS.iter().find(|p| p.number == number)
+}
+
+fn parse_u64(input: &str) -> u64 {
+    input.trim().parse().expect("Expected a single non-negative integer as input")
+}
+
+fn solve_2(input: &str) -> u64 {
+    fibs::sum_even_fibs(parse_u64(input) as u32) as u64
+}
+
+fn solve_3(input: &str) -> u64 {
+    let mut ps = primes::CachedPrimes::new();
+    primes::factorize(parse_u64(input), &mut ps).into_iter().max().unwrap_or(1)
+}
+
+fn solve_4(input: &str) -> u64 {
+    palindromes::largest_palindrome_of_product_of_n_digit_numbers(parse_u64(input) as u32) as u64
+}
+
+fn solve_5(input: &str) -> u64 {
+    let mut ps = primes::CachedPrimes::new();
+    ints::smallest_number_divisible_by_ns_from_1_to_n__fast(parse_u64(input), &mut ps)
+}
+
+fn solve_7(input: &str) -> u64 {
+    let mut ps = primes::CachedPrimes::new();
+    ps.iter().nth(parse_u64(input) as usize - 1).unwrap()
+}
+
+fn solve_8(input: &str) -> u64 {
+    let series = input.chars().filter_map(|c| c.to_digit(10)).map(|d| d as u64).collect();
+    subseq::largest_product_in_series(series, 13).unwrap_or(0)
+}
+
+fn solve_9(input: &str) -> u64 {
+    let sum = parse_u64(input) as u32;
+    let (a, b, c) = ints::PythagoreanTriplets::new()
+        .find(|&(a, b, c)| a + b + c == sum)
+        .unwrap();
+    a as u64 * b as u64 * c as u64
+}
+
+fn solve_10(input: &str) -> u64 {
+    let limit = parse_u64(input);
+    primes::primes_below(limit).iter().sum()
+}
+
+fn solve_11(input: &str) -> u64 {
+    let matrix = DenseMatrix::<u64>::from_grid(input).expect("Failed to parse grid matrix");
+
+    matrix.max_segment_product(4).map_or(0, |best| best.value)
+}
+
+fn solve_12(input: &str) -> u64 {
+    let mut ps = primes::CachedPrimes::new();
+    let min_divisors = parse_u64(input) as u32;
+    ints::Triangular::new()
+        .find(|&t| ints::divisor_count(t, &mut ps) > min_divisors)
+        .unwrap()
+}
+
+fn solve_14(input: &str) -> u64 {
+    let limit = parse_u64(input) as i64;
+    (1..limit)
+        .max_by_key(|&n| sequences::collatz(n).length(1))
+        .unwrap_or(0) as u64
+}
+
+fn solve_15(input: &str) -> u64 {
+    let size = parse_u64(input);
+    ints::binomial(2 * size, size)
+}
+
+fn solve_17(input: &str) -> u64 {
+    (1..parse_u64(input) as u32 + 1).map(numbers::number_letter_count).sum()
+}
+
+fn solve_18(input: &str) -> u64 {
+    triangles::longest_path_from_top_to_bottom(input) as u64
+}
+
+fn solve_19(input: &str) -> u64 {
+    let years: Vec<i32> = input
+        .split_whitespace()
+        .map(|y| y.parse().expect("Expected a year"))
+        .collect();
+    days::first_of_month_sundays(years[0], years[1]) as u64
+}
+
+fn solve_20(input: &str) -> u64 {
+    ints::digit_sum(ints::factorial(BigUint::from(parse_u64(input))))
+}
+
+fn solve_25(input: &str) -> u64 {
+    fibs::first_fib_index_with_digits(parse_u64(input) as usize) as u64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn problems_are_ordered_and_unique() {
+        let numbers: Vec<u32> = PROBLEMS.iter().map(|p| p.number).collect();
+        let mut sorted = numbers.clone();
+        sorted.sort();
+        sorted.dedup();
+
+        assert_eq!(numbers, sorted);
+    }
+
+    #[test]
+    fn find_by_number() {
+        assert_eq!(find(19).map(|p| p.title), Some("Counting Sundays"));
+        assert!(find(1).is_none());
+    }
+
+    #[test]
+    fn quick_solutions_match_known_answers() {
+        for &n in &[2, 3, 4, 5, 8, 11, 15, 17, 18, 19, 20, 25] {
+            let problem = find(n).unwrap();
+            assert_eq!(problem.solve(), problem.answer, "Problem {}", n);
+        }
+    }
+}